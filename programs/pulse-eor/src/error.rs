@@ -5,4 +5,71 @@ pub enum NovaError {
     NotEnoughBalanceError,
     PayerIsNotStreamAuthority,
     NotEmployeeTokenAccount,
+    MathOverflow,
+    InvalidFeeBps,
+    NotFeeRecipientTokenAccount,
+    NotHoldingWalletTokenAccount,
+    PayerIsNotAdmin,
+    InvalidPayPeriod,
+    AdvanceLimitExceeded,
+    NotContractPayee,
+    NotStreamingWalletTokenAccount,
+    ContractNotActive,
+    LoanAlreadyActive,
+    LoanNotActive,
+    InvalidLoanTerms,
+    InvalidLoanAccount,
+    MissingLoanAccounts,
+    TooManyDeductionRules,
+    InvalidDeductionAccount,
+    MissingDeductionAccounts,
+    TooManyContributionRules,
+    InvalidContributionAccount,
+    MissingContributionAccounts,
+    MissingApprovals,
+    InvalidExpenseClaim,
+    ExpenseClaimNotPending,
+    InvalidMilestones,
+    InvalidMilestoneStatus,
+    DisputeWindowOpen,
+    HourlyContractRequiresTimesheet,
+    ContractNotHourly,
+    InvalidTimesheet,
+    InvalidDispute,
+    DisputeWindowClosed,
+    InvalidTerminationReason,
+    InvalidLeaveRequest,
+    MissingPriceFeed,
+    InvalidPriceFeed,
+    StalePrice,
+    PriceConfidenceTooWide,
+    InvalidPayoutInstructions,
+    MissingPayoutAccounts,
+    InvalidTokenProgram,
+    InvalidTokenAccount,
+    InvalidRateOpening,
+    InvalidStandingOrder,
+    StandingOrderNotDue,
+    FundsLocked,
+    InvalidLockBucket,
+    NotHoldingWalletOwner,
+    DelegateLimitExceeded,
+    InvalidDelegate,
+    InvalidGuardians,
+    RebalanceNotNeeded,
+    VaultUnavailable,
+    InvalidHoldingWalletAccounts,
+    InvalidMigration,
+    AlreadyMigrated,
+    InvalidAdmin,
+    NotUpgradeAuthority,
+    InvalidEmployeeContract,
+    ExpenseClaimNotApproved,
+    MilestoneNotDue,
+    OverlappingTimesheet,
+    OverlappingLeaveRequest,
+    UnsupportedHoldingWalletMint,
+    RecoveryPending,
+    RecoveryNotReady,
+    ContractNotSalaried,
 }
\ No newline at end of file
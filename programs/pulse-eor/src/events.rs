@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    ContributionCategory, DeductionCategory, DisputeStatus, ExpenseCategory, ExpenseClaimStatus, LeaveRequestStatus, MilestoneStatus,
+    PaymentKind, TerminationReason, TimesheetStatus,
+};
+
+#[event]
+pub struct YieldRealised {
+    pub payee: Pubkey,
+    pub organisation_id: String,
+    pub lp_amount: u64,
+    pub underlying_amount: u64,
+    pub cost_basis: u64,
+    pub realised_yield: u64,
+    pub performance_fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdvanceIssued {
+    pub employee_contract: Pubkey,
+    pub payee: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub outstanding_advance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdvanceRecovered {
+    pub employee_contract: Pubkey,
+    pub amount: u64,
+    pub outstanding_advance: u64,
+}
+
+#[event]
+pub struct LoanIssued {
+    pub loan: Pubkey,
+    pub employee_contract: Pubkey,
+    pub principal: u64,
+    pub interest_rate_bps: u16,
+    pub term: u16,
+    pub installment_amount: u64,
+    pub total_due: u64,
+}
+
+#[event]
+pub struct LoanRepayment {
+    pub loan: Pubkey,
+    pub amount: u64,
+    pub remaining_balance: u64,
+    pub early: bool,
+}
+
+#[event]
+pub struct LoanDefaulted {
+    pub loan: Pubkey,
+    pub employee_contract: Pubkey,
+    pub remaining_balance: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DeductionLine {
+    pub category: DeductionCategory,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ContributionLine {
+    pub category: ContributionCategory,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct Payslip {
+    pub employee_contract: Pubkey,
+    pub payment_index: u64,
+    pub payment_kind: PaymentKind,
+    pub gross_amount: u64,
+    pub deductions: Vec<DeductionLine>,
+    pub advance_recovered: u64,
+    pub loan_installment: u64,
+    pub unpaid_leave_withheld: u64,
+    pub transfer_fee: u64,
+    pub net_amount: u64,
+    pub employer_contributions: Vec<ContributionLine>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseClaimSubmitted {
+    pub expense_claim: Pubkey,
+    pub employee_contract: Pubkey,
+    pub amount: u64,
+    pub category: ExpenseCategory,
+    pub receipt_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpenseClaimResolved {
+    pub expense_claim: Pubkey,
+    pub status: ExpenseClaimStatus,
+    pub amount: u64,
+    pub resolver: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneUpdated {
+    pub milestone_contract: Pubkey,
+    pub index: u8,
+    pub status: MilestoneStatus,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TimesheetUpdated {
+    pub timesheet: Pubkey,
+    pub employee_contract: Pubkey,
+    pub status: TimesheetStatus,
+    pub hours: u32,
+    pub payout: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeUpdated {
+    pub dispute: Pubkey,
+    pub receipt: Pubkey,
+    pub status: DisputeStatus,
+    pub actor: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+
+#[event]
+pub struct ContractTerminated {
+    pub employee_contract: Pubkey,
+    pub reason: TerminationReason,
+    pub payment_index: u64,
+    pub pro_rata_amount: u64,
+    pub severance_amount: u64,
+    pub leave_payout: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LeaveUpdated {
+    pub leave_request: Pubkey,
+    pub employee_contract: Pubkey,
+    pub status: LeaveRequestStatus,
+    pub days: u16,
+    pub unpaid_units: u64,
+    pub leave_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StandingOrderExecuted {
+    pub standing_order: Pubkey,
+    pub payee: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub lp_unwound: u64,
+    pub performance_fee: u64,
+    pub next_execution_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LockUpdated {
+    pub holding_wallet_state: Pubkey,
+    pub name: String,
+    pub amount: u64,
+    pub unlock_at: i64,
+    pub released: bool,
+    pub penalty: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HoldingWalletRecovered {
+    pub holding_wallet_state: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HoldingWalletRebalanced {
+    pub holding_wallet_state: Pubkey,
+    pub keeper: Pubkey,
+    pub drift_bps: u64,
+    pub deposited: u64,
+    pub lp_withdrawn: u64,
+    pub bounty: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyUnwound {
+    pub holding_wallet_state: Pubkey,
+    pub lp_withdrawn: u64,
+    pub lp_remaining: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LegacyHoldingWalletSwept {
+    pub holding_wallet_state: Pubkey,
+    pub idle_amount: u64,
+    pub lp_amount: u64,
+    pub lp_value: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrganisationAdminUpdated {
+    pub organisation: Pubkey,
+    pub admin: Pubkey,
+    pub removed: bool,
+    pub admin_count: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HoldingWalletRecoveryStarted {
+    pub holding_wallet_state: Pubkey,
+    pub new_owner: Pubkey,
+    pub guardians: Vec<Pubkey>,
+    pub available_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HoldingWalletRecoveryCancelled {
+    pub holding_wallet_state: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
\ No newline at end of file
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::events::AdvanceIssued;
+use crate::oracle::{load_contract_quote, to_payout_mint};
+use crate::state::{ContractStatus, EmployeeContract, Organisation, PayMode, RateOpening};
+use crate::token_interface::{is_token_program, token_account};
+use crate::utils::{transfer_from_streaming_wallet, BPS_DENOMINATOR};
+
+pub fn configure_advances(
+    ctx: Context<ConfigureAdvances>,
+    _organisation_id: String,
+    advance_limit_bps: u16,
+    advance_fee: u64,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if advance_limit_bps as u64 > BPS_DENOMINATOR {
+        return err!(NovaError::InvalidFeeBps)
+    }
+
+    let organisation = &mut ctx.accounts.organisation;
+    organisation.advance_limit_bps = advance_limit_bps;
+    organisation.advance_fee = advance_fee;
+
+    Ok(())
+}
+
+// The limit comes from salary accrued over the pay period, hourly contracts accrue nothing
+// until a timesheet is approved and cannot take advances
+pub fn request_advance(
+    ctx: Context<RequestAdvance>,
+    _organisation_id: String,
+    _employee_id: String,
+    amount: u64,
+    rate_opening: Option<RateOpening>,
+) -> Result<()> {
+    if ctx.accounts.employee_contract.status != ContractStatus::Active {
+        return err!(NovaError::ContractNotActive)
+    }
+    if ctx.accounts.employee_contract.pay_mode == PayMode::Hourly {
+        return err!(NovaError::ContractNotSalaried)
+    }
+
+    let rate = ctx.accounts.employee_contract.open_rate(rate_opening.as_ref())?;
+    let now = Clock::get()?.unix_timestamp;
+    let organisation = &ctx.accounts.organisation;
+    let fee = organisation.advance_fee;
+    let owed = amount.checked_add(fee).ok_or(NovaError::MathOverflow)?;
+
+    // Remaining accounts: the price feed for contracts not denominated in USD
+    let quote = load_contract_quote(&mut ctx.remaining_accounts.iter(), &ctx.accounts.employee_contract, organisation, now)?;
+    let advance_limit = to_payout_mint(
+        quote.as_ref(),
+        ctx.accounts.employee_contract.advance_limit(rate, now, organisation.advance_limit_bps),
+    )?;
+    if owed > advance_limit.saturating_sub(ctx.accounts.employee_contract.outstanding_advance) {
+        return err!(NovaError::AdvanceLimitExceeded)
+    }
+    if token_account(&ctx.accounts.streaming_wallet_token_account)?.amount < amount {
+        return err!(NovaError::NotEnoughBalanceError)
+    }
+
+    let employee_contract = &mut ctx.accounts.employee_contract;
+    employee_contract.outstanding_advance += owed;
+
+    transfer_from_streaming_wallet(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.streaming_wallet.to_account_info(),
+        ctx.accounts.streaming_wallet_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.payee_token_account.to_account_info(),
+        &_organisation_id,
+        ctx.accounts.organisation.stream_wallet_bump,
+        amount,
+    )?;
+
+    emit!(AdvanceIssued {
+        employee_contract: ctx.accounts.employee_contract.key(),
+        payee: ctx.accounts.payee.key(),
+        amount,
+        fee,
+        outstanding_advance: ctx.accounts.employee_contract.outstanding_advance,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct ConfigureAdvances<'info> {
+    #[account(
+        mut,
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Account<'info, Organisation>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct RequestAdvance<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+        constraint = employee_contract.payee == payee.key() @ NovaError::NotContractPayee,
+    )]
+    pub employee_contract: Account<'info, EmployeeContract>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Account<'info, Organisation>,
+    #[account(
+        seeds = [b"streaming-wallet", _organisation_id.as_bytes()],
+        bump = organisation.stream_wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub streaming_wallet: AccountInfo<'info>,
+    #[account(mut)]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub streaming_wallet_token_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_account(&payee_token_account)?.owner == payee.key() @ NovaError::NotEmployeeTokenAccount,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub payee_token_account: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub mint: AccountInfo<'info>,
+    pub payee: Signer<'info>,
+    #[account(constraint = is_token_program(token_program.key) @ NovaError::InvalidTokenProgram)]
+    /// CHECK: This is not dangerous because it must be one of the token programs
+    pub token_program: AccountInfo<'info>,
+}
\ No newline at end of file
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::events::ContributionLine;
+use crate::state::{
+    ContributionRule, ContributionSchedule, EmployeeContract, Organisation, MAX_CONTRIBUTION_RULES,
+};
+use crate::utils::{transfer_from_streaming_wallet, BPS_DENOMINATOR};
+
+// Replaces the contract's employer contribution rules, `paid_to_date` restarts at zero
+pub fn set_contribution_rules(
+    ctx: Context<SetContributionRules>,
+    _organisation_id: String,
+    _employee_id: String,
+    rules: Vec<ContributionRule>,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if rules.len() > MAX_CONTRIBUTION_RULES {
+        return err!(NovaError::TooManyContributionRules)
+    }
+
+    let contribution_schedule = &mut ctx.accounts.contribution_schedule;
+    contribution_schedule.employee_contract = ctx.accounts.employee_contract.key();
+    contribution_schedule.bump = *ctx.bumps.get("contribution_schedule").unwrap();
+    contribution_schedule.rules = rules
+        .into_iter()
+        .map(|rule| ContributionRule { paid_to_date: 0, ..rule })
+        .collect();
+
+    let total_bps = contribution_schedule.total_bps();
+    if total_bps > BPS_DENOMINATOR {
+        return err!(NovaError::InvalidFeeBps)
+    }
+
+    let employee_contract = &mut ctx.accounts.employee_contract;
+    employee_contract.employer_contribution_bps = total_bps as u16;
+    employee_contract.contributions = if contribution_schedule.rules.is_empty() {
+        Pubkey::default()
+    } else {
+        contribution_schedule.key()
+    };
+
+    Ok(())
+}
+
+// Pays each employer contribution on `gross_amount` from the streaming wallet on top of the
+// payment. Expects the contribution schedule followed by one destination token account per
+// rule as the next remaining accounts when the contract carries contributions.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_employer_contributions<'info>(
+    remaining_accounts: &mut std::slice::Iter<AccountInfo<'info>>,
+    employee_contract: &mut EmployeeContract,
+    token_program: AccountInfo<'info>,
+    streaming_wallet: AccountInfo<'info>,
+    streaming_wallet_token_account: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    organisation_id: &str,
+    stream_wallet_bump: u8,
+    gross_amount: u64,
+) -> Result<Vec<ContributionLine>> {
+    let mut lines = Vec::new();
+    if !employee_contract.has_contributions() {
+        return Ok(lines)
+    }
+
+    let schedule_info = remaining_accounts.next().ok_or(NovaError::MissingContributionAccounts)?;
+    if schedule_info.key() != employee_contract.contributions {
+        return err!(NovaError::InvalidContributionAccount)
+    }
+    let mut schedule: Account<ContributionSchedule> = Account::try_from(schedule_info)?;
+
+    for rule in schedule.rules.iter_mut() {
+        let destination = remaining_accounts.next().ok_or(NovaError::MissingContributionAccounts)?;
+        if destination.key() != rule.destination {
+            return err!(NovaError::InvalidContributionAccount)
+        }
+
+        let amount = rule.amount(gross_amount);
+        if amount > 0 {
+            transfer_from_streaming_wallet(
+                token_program.clone(),
+                streaming_wallet.clone(),
+                streaming_wallet_token_account.clone(),
+                mint.clone(),
+                destination.clone(),
+                organisation_id,
+                stream_wallet_bump,
+                amount,
+            )?;
+        }
+        rule.paid_to_date = rule.paid_to_date.checked_add(amount).ok_or(NovaError::MathOverflow)?;
+        employee_contract.total_employer_contributions = employee_contract
+            .total_employer_contributions
+            .checked_add(amount)
+            .ok_or(NovaError::MathOverflow)?;
+
+        lines.push(ContributionLine {
+            category: rule.category,
+            destination: rule.destination,
+            amount,
+        });
+    }
+    schedule.exit(&crate::ID)?;
+
+    Ok(lines)
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct SetContributionRules<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + ContributionSchedule::SIZE,
+        seeds = [b"contributions", employee_contract.key().as_ref()],
+        bump
+    )]
+    pub contribution_schedule: Box<Account<'info, ContributionSchedule>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
\ No newline at end of file
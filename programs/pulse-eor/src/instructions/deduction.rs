@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::events::DeductionLine;
+use crate::oracle::PriceQuote;
+use crate::state::{
+    DeductionBasis, DeductionRule, DeductionSchedule, EmployeeContract, Organisation,
+    MAX_DEDUCTION_RULES,
+};
+use crate::utils::{transfer_from_streaming_wallet, BPS_DENOMINATOR};
+
+pub fn set_deduction_rules(
+    ctx: Context<SetDeductionRules>,
+    _organisation_id: String,
+    _employee_id: String,
+    rules: Vec<DeductionRule>,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if rules.len() > MAX_DEDUCTION_RULES {
+        return err!(NovaError::TooManyDeductionRules)
+    }
+    let total_bps = rules
+        .iter()
+        .filter(|rule| rule.basis == DeductionBasis::Bps)
+        .try_fold(0u64, |total, rule| total.checked_add(rule.value))
+        .ok_or(NovaError::InvalidFeeBps)?;
+    if total_bps > BPS_DENOMINATOR {
+        return err!(NovaError::InvalidFeeBps)
+    }
+
+    let deduction_schedule = &mut ctx.accounts.deduction_schedule;
+    deduction_schedule.employee_contract = ctx.accounts.employee_contract.key();
+    deduction_schedule.bump = *ctx.bumps.get("deduction_schedule").unwrap();
+    deduction_schedule.rules = rules;
+
+    ctx.accounts.employee_contract.deductions = if deduction_schedule.rules.is_empty() {
+        Pubkey::default()
+    } else {
+        deduction_schedule.key()
+    };
+
+    Ok(())
+}
+
+// Splits each deduction off `gross_amount` from the streaming wallet to its destination, fixed
+// deductions are converted from the contract's currency at `quote`. Expects the deduction schedule followed by one destination token account per rule as
+// the next remaining accounts when the contract carries deductions.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_deductions<'info>(
+    remaining_accounts: &mut std::slice::Iter<AccountInfo<'info>>,
+    employee_contract: &EmployeeContract,
+    token_program: AccountInfo<'info>,
+    streaming_wallet: AccountInfo<'info>,
+    streaming_wallet_token_account: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    organisation_id: &str,
+    stream_wallet_bump: u8,
+    quote: Option<&PriceQuote>,
+    gross_amount: u64,
+) -> Result<Vec<DeductionLine>> {
+    let mut lines = Vec::new();
+    if !employee_contract.has_deductions() {
+        return Ok(lines)
+    }
+
+    let schedule_info = remaining_accounts.next().ok_or(NovaError::MissingDeductionAccounts)?;
+    if schedule_info.key() != employee_contract.deductions {
+        return err!(NovaError::InvalidDeductionAccount)
+    }
+    let schedule: Account<DeductionSchedule> = Account::try_from(schedule_info)?;
+
+    let mut remaining_gross = gross_amount;
+    for rule in schedule.rules.iter() {
+        let destination = remaining_accounts.next().ok_or(NovaError::MissingDeductionAccounts)?;
+        if destination.key() != rule.destination {
+            return err!(NovaError::InvalidDeductionAccount)
+        }
+
+        let amount = rule.amount(gross_amount, quote)?.min(remaining_gross);
+        remaining_gross -= amount;
+        if amount > 0 {
+            transfer_from_streaming_wallet(
+                token_program.clone(),
+                streaming_wallet.clone(),
+                streaming_wallet_token_account.clone(),
+                mint.clone(),
+                destination.clone(),
+                organisation_id,
+                stream_wallet_bump,
+                amount,
+            )?;
+        }
+
+        lines.push(DeductionLine {
+            category: rule.category,
+            destination: rule.destination,
+            amount,
+        });
+    }
+
+    Ok(lines)
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct SetDeductionRules<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + DeductionSchedule::SIZE,
+        seeds = [b"deductions", employee_contract.key().as_ref()],
+        bump
+    )]
+    pub deduction_schedule: Box<Account<'info, DeductionSchedule>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
\ No newline at end of file
@@ -0,0 +1,313 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::events::DisputeUpdated;
+use crate::instructions::stream::verify_admin_approvals;
+use crate::state::{
+    Dispute, DisputeStatus, EmployeeContract, HoldingWalletState, Organisation, PaymentReceipt,
+};
+use crate::token_interface::{is_token_program, token_account, transfer_checked};
+
+pub fn configure_dispute_policy(
+    ctx: Context<ConfigureDisputePolicy>,
+    _organisation_id: String,
+    dispute_window: i64,
+    arbiter: Pubkey,
+    dispute_approvals: u8,
+) -> Result<()> {
+    let organisation = &mut ctx.accounts.organisation;
+    if !organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if dispute_window < 0 || dispute_approvals as usize > organisation.admins.len() {
+        return err!(NovaError::InvalidDispute)
+    }
+
+    organisation.dispute_window = dispute_window;
+    organisation.arbiter = arbiter;
+    organisation.dispute_approvals = dispute_approvals;
+
+    Ok(())
+}
+
+pub fn open_dispute(
+    ctx: Context<OpenDispute>,
+    _organisation_id: String,
+    _employee_id: String,
+    _payment_index: u64,
+    amount: u64,
+    reason_hash: [u8; 32],
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let receipt = &ctx.accounts.receipt;
+    if now > receipt.paid_at.saturating_add(ctx.accounts.organisation.dispute_window) {
+        return err!(NovaError::DisputeWindowClosed)
+    }
+    if amount == 0 || amount > receipt.net_amount {
+        return err!(NovaError::InvalidDispute)
+    }
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.receipt = receipt.key();
+    dispute.employee_contract = ctx.accounts.employee_contract.key();
+    dispute.reason_hash = reason_hash;
+    dispute.amount = amount;
+    dispute.clawed_back_amount = 0;
+    dispute.bump = *ctx.bumps.get("dispute").unwrap();
+    dispute.history = Vec::new();
+    dispute.record(DisputeStatus::Open, ctx.accounts.admin.key(), amount, now);
+
+    emit!(DisputeUpdated {
+        dispute: dispute.key(),
+        receipt: dispute.receipt,
+        status: DisputeStatus::Open,
+        actor: ctx.accounts.admin.key(),
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Signed by the organisation's arbiter, or else the remaining accounts must carry
+// `dispute_approvals` distinct admin signers
+pub fn resolve_dispute(
+    ctx: Context<ResolveDispute>,
+    _organisation_id: String,
+    _employee_id: String,
+    _payment_index: u64,
+    uphold: bool,
+    amount: u64,
+) -> Result<()> {
+    let organisation = &ctx.accounts.organisation;
+    let is_arbiter = organisation.arbiter != Pubkey::default() && organisation.arbiter == ctx.accounts.resolver.key();
+    if !is_arbiter {
+        if organisation.dispute_approvals == 0 {
+            return err!(NovaError::MissingApprovals)
+        }
+        verify_admin_approvals(&mut ctx.remaining_accounts.iter(), organisation, organisation.dispute_approvals)?;
+    }
+    if ctx.accounts.dispute.status != DisputeStatus::Open {
+        return err!(NovaError::InvalidDispute)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let dispute = &mut ctx.accounts.dispute;
+    if uphold {
+        if amount == 0 || amount > dispute.amount {
+            return err!(NovaError::InvalidDispute)
+        }
+        dispute.amount = amount;
+        dispute.record(DisputeStatus::Upheld, ctx.accounts.resolver.key(), amount, now);
+    } else {
+        dispute.amount = 0;
+        dispute.record(DisputeStatus::Dismissed, ctx.accounts.resolver.key(), 0, now);
+    }
+
+    emit!(DisputeUpdated {
+        dispute: dispute.key(),
+        receipt: dispute.receipt,
+        status: dispute.status,
+        actor: ctx.accounts.resolver.key(),
+        amount: dispute.amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Claws back as much of the upheld amount as is still idle and unlocked in the employee's
+// holding wallet, capped at what the disputed payment credited to it
+pub fn execute_clawback(
+    ctx: Context<ExecuteClawback>,
+    _organisation_id: String,
+    _employee_id: String,
+    _payment_index: u64,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.dispute.status != DisputeStatus::Upheld {
+        return err!(NovaError::InvalidDispute)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let idle_balance = token_account(&ctx.accounts.holding_wallet_token_account)?.amount;
+    let unlocked_balance = idle_balance.saturating_sub(ctx.accounts.holding_wallet_state.locked_amount(now));
+    let amount = ctx.accounts.dispute.amount
+        .min(ctx.accounts.receipt.holding_wallet_amount)
+        .min(unlocked_balance);
+    if amount == 0 {
+        return err!(NovaError::NotEnoughBalanceError)
+    }
+
+    let payee = ctx.accounts.employee_contract.payee;
+    let signer_seeds = &[
+        b"holding-wallet".as_ref(),
+        payee.as_ref(),
+        _organisation_id.as_bytes(),
+        &[ctx.accounts.holding_wallet_state.wallet_bump],
+    ];
+    let signer = &[&signer_seeds[..]];
+
+    transfer_checked(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.holding_wallet_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.streaming_wallet_token_account.to_account_info(),
+        ctx.accounts.holding_wallet.to_account_info(),
+        signer,
+        amount,
+    )?;
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.clawed_back_amount = amount;
+    dispute.record(DisputeStatus::ClawedBack, ctx.accounts.admin.key(), amount, now);
+
+    emit!(DisputeUpdated {
+        dispute: dispute.key(),
+        receipt: dispute.receipt,
+        status: DisputeStatus::ClawedBack,
+        actor: ctx.accounts.admin.key(),
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct ConfigureDisputePolicy<'info> {
+    #[account(
+        mut,
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Account<'info, Organisation>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, _payment_index: u64)]
+pub struct OpenDispute<'info> {
+    #[account(
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        seeds = [b"payment-receipt", employee_contract.key().as_ref(), &_payment_index.to_le_bytes()],
+        bump = receipt.bump,
+    )]
+    pub receipt: Box<Account<'info, PaymentReceipt>>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Dispute::SIZE,
+        seeds = [b"dispute", receipt.key().as_ref()],
+        bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, _payment_index: u64)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        seeds = [b"payment-receipt", employee_contract.key().as_ref(), &_payment_index.to_le_bytes()],
+        bump = receipt.bump,
+    )]
+    pub receipt: Box<Account<'info, PaymentReceipt>>,
+    #[account(
+        mut,
+        seeds = [b"dispute", receipt.key().as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    pub resolver: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, _payment_index: u64)]
+pub struct ExecuteClawback<'info> {
+    #[account(
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        seeds = [b"payment-receipt", employee_contract.key().as_ref(), &_payment_index.to_le_bytes()],
+        bump = receipt.bump,
+    )]
+    pub receipt: Box<Account<'info, PaymentReceipt>>,
+    #[account(
+        mut,
+        seeds = [b"dispute", receipt.key().as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        seeds = [b"holding-state", employee_contract.payee.as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.bump,
+    )]
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
+    #[account(
+        seeds = [b"holding-wallet", employee_contract.payee.as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub holding_wallet: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_account(&holding_wallet_token_account)?.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_token_account.key() == receipt.holding_wallet_token_account @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub holding_wallet_token_account: AccountInfo<'info>,
+    #[account(
+        seeds = [b"streaming-wallet", _organisation_id.as_bytes()],
+        bump = organisation.stream_wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub streaming_wallet: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_account(&streaming_wallet_token_account)?.owner == streaming_wallet.key() @ NovaError::NotStreamingWalletTokenAccount,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub streaming_wallet_token_account: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub mint: AccountInfo<'info>,
+    pub admin: Signer<'info>,
+    #[account(constraint = is_token_program(token_program.key) @ NovaError::InvalidTokenProgram)]
+    /// CHECK: This is not dangerous because it must be one of the token programs
+    pub token_program: AccountInfo<'info>,
+}
\ No newline at end of file
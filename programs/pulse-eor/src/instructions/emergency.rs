@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use mercurial_vault::{cpi::accounts::DepositWithdrawLiquidity, program::Vault};
+
+use crate::error::NovaError;
+use crate::events::{EmergencyUnwound, YieldRealised};
+use crate::state::{HoldingWalletState, Organisation};
+use crate::utils::{lp_to_underlying, vault_can_pay_out};
+
+// Pulls the LP of each given holding wallet of the organisation out of the vault, waiving the
+// performance fee, and moves its allocation fully to the idle balance so later payments stay out
+// of the vault. LP the vault can't pay out stays in it. Remaining accounts: for each holding
+// wallet its state, the wallet, its token account and its LP token account.
+pub fn emergency_unwind<'info>(
+    ctx: Context<'_, '_, '_, 'info, EmergencyUnwind<'info>>,
+    _organisation_id: String,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut remaining_accounts = ctx.remaining_accounts.iter();
+    while let Some(state_info) = remaining_accounts.next() {
+        let holding_wallet = remaining_accounts.next().ok_or(NovaError::InvalidHoldingWalletAccounts)?;
+        let token_info = remaining_accounts.next().ok_or(NovaError::InvalidHoldingWalletAccounts)?;
+        let lp_info = remaining_accounts.next().ok_or(NovaError::InvalidHoldingWalletAccounts)?;
+
+        let mut holding_wallet_state: Account<HoldingWalletState> = Account::try_from(state_info)?;
+        let payee = holding_wallet_state.payee;
+        let state_address = Pubkey::create_program_address(
+            &[b"holding-state", payee.as_ref(), _organisation_id.as_bytes(), &[holding_wallet_state.bump]],
+            &crate::ID,
+        ).map_err(|_| error!(NovaError::InvalidHoldingWalletAccounts))?;
+        let wallet_address = Pubkey::create_program_address(
+            &[b"holding-wallet", payee.as_ref(), _organisation_id.as_bytes(), &[holding_wallet_state.wallet_bump]],
+            &crate::ID,
+        ).map_err(|_| error!(NovaError::InvalidHoldingWalletAccounts))?;
+        if state_address != state_info.key() || wallet_address != holding_wallet.key() {
+            return err!(NovaError::InvalidHoldingWalletAccounts)
+        }
+
+        let token_account: Account<TokenAccount> = Account::try_from(token_info)?;
+        let lp_account: Account<TokenAccount> = Account::try_from(lp_info)?;
+        if token_account.owner != wallet_address || token_account.mint != ctx.accounts.vault.token_mint {
+            return err!(NovaError::NotHoldingWalletTokenAccount)
+        }
+        if lp_account.owner != wallet_address || lp_account.mint != ctx.accounts.lp_mint.key() {
+            return err!(NovaError::NotHoldingWalletTokenAccount)
+        }
+
+        let lp_balance = lp_account.amount;
+        let underlying_amount = lp_to_underlying(&ctx.accounts.vault, lp_balance, ctx.accounts.lp_mint.supply)?;
+        let mut lp_withdrawn = 0;
+        if lp_balance > 0 && vault_can_pay_out(&ctx.accounts.vault, ctx.accounts.token_vault.amount, underlying_amount) {
+            let signer_seeds = &[
+                b"holding-wallet".as_ref(),
+                payee.as_ref(),
+                _organisation_id.as_bytes(),
+                &[holding_wallet_state.wallet_bump],
+            ];
+            let signer: &[&[&[u8]]; 1] = &[&signer_seeds[..]];
+            let mecurial_accounts = DepositWithdrawLiquidity {
+                vault: ctx.accounts.vault.to_account_info(),
+                token_vault: ctx.accounts.token_vault.to_account_info(),
+                lp_mint: ctx.accounts.lp_mint.to_account_info(),
+                user_token: token_info.clone(),
+                user_lp: lp_info.clone(),
+                user: holding_wallet.clone(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            };
+            let mecurial_context = CpiContext::new(
+                ctx.accounts.mercurial_program.to_account_info(),
+                mecurial_accounts,
+            ).with_signer(signer);
+            mercurial_vault::cpi::withdraw(mecurial_context, lp_balance, 0)?;
+            lp_withdrawn = lp_balance;
+
+            // The next holding wallet is priced against the vault after this exit
+            ctx.accounts.vault.reload()?;
+            ctx.accounts.token_vault.reload()?;
+            ctx.accounts.lp_mint.reload()?;
+
+            let (cost_basis, realised_yield) = holding_wallet_state.realise(lp_balance, lp_balance, underlying_amount);
+            emit!(YieldRealised {
+                payee,
+                organisation_id: _organisation_id.clone(),
+                lp_amount: lp_balance,
+                underlying_amount,
+                cost_basis,
+                realised_yield,
+                performance_fee: 0,
+                timestamp: now,
+            });
+        }
+
+        holding_wallet_state.meteora_allocation = 0;
+        holding_wallet_state.holding_allocation = 100;
+        holding_wallet_state.exit(&crate::ID)?;
+
+        emit!(EmergencyUnwound {
+            holding_wallet_state: state_info.key(),
+            lp_withdrawn,
+            lp_remaining: lp_balance - lp_withdrawn,
+            timestamp: now,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct EmergencyUnwind<'info> {
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = token_vault,
+        has_one = lp_mint,
+    )]
+    pub vault: Box<Account<'info, mercurial_vault::state::Vault>>,
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub mercurial_program: Program<'info, Vault>,
+}
\ No newline at end of file
@@ -1,8 +1,14 @@
-use crate::state::HoldingWalletState;
+use crate::error::NovaError;
+use crate::events::YieldRealised;
+use crate::instructions::payout::distribute_payout;
+use crate::state::{HoldingWalletState, Organisation, ProtocolConfig};
+use crate::utils::{lp_to_underlying, vault_can_pay_out};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount, Transfer, self};
 use mercurial_vault::{cpi::accounts::DepositWithdrawLiquidity, program::Vault};
 
+// Each organisation pays into its own holding wallet, seeded by the payee and the organisation id,
+// so the state's locks, principal and authority cover all of its wallet's funds
 pub fn setup_holding_wallet(
     ctx: Context<SetupHoldingWallet>,
     _organisation_id: String,
@@ -18,12 +24,28 @@ pub fn setup_holding_wallet(
 
     holding_wallet_account.meteora_allocation = 40;
     holding_wallet_account.holding_allocation = 60;
+    holding_wallet_account.principal = 0;
+    holding_wallet_account.locks = Vec::new();
+    holding_wallet_account.owner = *ctx.accounts.employee.key;
+    holding_wallet_account.delegate = Pubkey::default();
+    holding_wallet_account.delegate_limit = 0;
+    holding_wallet_account.delegate_period = 0;
+    holding_wallet_account.delegate_period_start = 0;
+    holding_wallet_account.delegate_spent = 0;
+    holding_wallet_account.guardians = Vec::new();
+    holding_wallet_account.guardian_threshold = 0;
+    holding_wallet_account.pending_owner = Pubkey::default();
+    holding_wallet_account.recovery_available_at = 0;
+    holding_wallet_account.version = HoldingWalletState::VERSION;
+    holding_wallet_account.reserved = [0; 64];
 
     Ok(())
 }
 
-pub fn employee_withdraw(
-    ctx: Context<EmployeeWithdraw>,
+// Withdraws for the holding wallet's owner, or its delegate within the delegate's limit.
+// Remaining accounts: the withdrawer's payout instructions and their destinations
+pub fn employee_withdraw<'info>(
+    ctx: Context<'_, '_, '_, 'info, EmployeeWithdraw<'info>>,
     _organisation_id: String,
     amount: u64,
     virtual_price: f64,
@@ -36,17 +58,52 @@ pub fn employee_withdraw(
     // if amount > total_usdc {
     //     panic!("Not enough balance")
     // }
+    let lp_value = lp_to_underlying(
+        &ctx.accounts.vault,
+        ctx.accounts.holding_wallet_lp_token_account.amount,
+        ctx.accounts.lp_mint.supply,
+    )?;
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.holding_wallet_state.ensure_unlocked(
+        ctx.accounts.holding_wallet_token_account.amount.saturating_add(lp_value),
+        amount,
+        now,
+    )?;
+    if ctx.accounts.withdrawer.key() != ctx.accounts.holding_wallet_state.owner {
+        ctx.accounts.holding_wallet_state.spend_delegate_allowance(amount, now)?;
+    }
+
     let holding_allocation = ctx.accounts.holding_wallet_state.holding_allocation;
 
     let holding_amount_to_withdraw = amount * holding_allocation as u64 / 100;
     let meteora_amount_to_withdraw = amount - holding_amount_to_withdraw;
     let num_lp_mint_to_withdraw = (meteora_amount_to_withdraw as f64 / virtual_price) as u64;
 
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.holding_wallet_token_account.to_account_info(),
-        to: ctx.accounts.withdrawer_token_account.to_account_info(),
-        authority: ctx.accounts.holding_wallet.to_account_info(),
-    };
+    let underlying_amount = lp_to_underlying(
+        &ctx.accounts.vault,
+        num_lp_mint_to_withdraw,
+        ctx.accounts.lp_mint.supply,
+    )?;
+    // A vault that can't pay out would fail the whole withdrawal, the idle balance covers all of
+    // it instead and the LP stays in the vault
+    let (num_lp_mint_to_withdraw, underlying_amount) =
+        if vault_can_pay_out(&ctx.accounts.vault, ctx.accounts.token_vault.amount, underlying_amount) {
+            (num_lp_mint_to_withdraw, underlying_amount)
+        } else if ctx.accounts.holding_wallet_token_account.amount >= amount {
+            (0, 0)
+        } else {
+            return err!(NovaError::VaultUnavailable)
+        };
+    let (cost_basis, realised_yield) = ctx.accounts.holding_wallet_state.realise(
+        num_lp_mint_to_withdraw,
+        ctx.accounts.holding_wallet_lp_token_account.amount,
+        underlying_amount,
+    );
+    let performance_fee = ctx.accounts.protocol_config.performance_fee(realised_yield);
+    if performance_fee > amount {
+        return err!(NovaError::NotEnoughBalanceError)
+    }
+
     let cpi_program = ctx.accounts.token_program.to_account_info();
 
     let cpi_mecurial_accounts = DepositWithdrawLiquidity {
@@ -62,16 +119,53 @@ pub fn employee_withdraw(
 
     let signer_seeds = &[
         b"holding-wallet".as_ref(),
-        ctx.accounts.withdrawer.key.as_ref(),
+        ctx.accounts.payee.key.as_ref(),
+        _organisation_id.as_bytes(),
         &[ctx.accounts.holding_wallet_state.wallet_bump],
     ];
     let signer: &[&[&[u8]]; 1] = &[&signer_seeds[..]];
 
     let cpi_mercurial_withdraw_context = CpiContext::new(cpi_mercurial_program, cpi_mecurial_accounts).with_signer(signer);
-    let cpi_context = CpiContext::new(cpi_program, cpi_accounts).with_signer(signer);
 
-    mercurial_vault::cpi::withdraw(cpi_mercurial_withdraw_context, num_lp_mint_to_withdraw, 0)?;
-    token::transfer(cpi_context, amount)?;
+    if num_lp_mint_to_withdraw > 0 {
+        mercurial_vault::cpi::withdraw(cpi_mercurial_withdraw_context, num_lp_mint_to_withdraw, 0)?;
+    }
+    distribute_payout(
+        &mut ctx.remaining_accounts.iter(),
+        ctx.accounts.withdrawer.key,
+        &_organisation_id,
+        cpi_program.clone(),
+        ctx.accounts.holding_wallet.to_account_info(),
+        ctx.accounts.holding_wallet_token_account.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.withdrawer_token_account.to_account_info(),
+        signer,
+        amount - performance_fee,
+    )?;
+
+    if performance_fee > 0 {
+        let fee_accounts = Transfer {
+            from: ctx.accounts.holding_wallet_token_account.to_account_info(),
+            to: ctx.accounts.protocol_fee_token_account.to_account_info(),
+            authority: ctx.accounts.holding_wallet.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(cpi_program, fee_accounts).with_signer(signer),
+            performance_fee,
+        )?;
+    }
+
+    emit!(YieldRealised {
+        payee: ctx.accounts.payee.key(),
+        organisation_id: _organisation_id,
+        lp_amount: num_lp_mint_to_withdraw,
+        underlying_amount,
+        cost_basis,
+        realised_yield,
+        performance_fee,
+        timestamp: now,
+    });
+
     Ok(())
 }
 
@@ -83,12 +177,38 @@ pub fn adjust_meteora_allocation(
     amount_to_deposit_to_mercurial: u64,
 ) -> Result<()> {
     let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
-    holding_wallet_state.meteora_allocation = adjusted_meteora_allocation.clone();
-    holding_wallet_state.holding_allocation = 100 - adjusted_meteora_allocation.clone();
+    holding_wallet_state.meteora_allocation = adjusted_meteora_allocation;
+    holding_wallet_state.holding_allocation = 100 - adjusted_meteora_allocation;
+
+    let is_withdrawal = amount_to_withdraw_from_mercurial > 0 && amount_to_deposit_to_mercurial == 0;
+    let mut underlying_amount = 0;
+    let mut cost_basis = 0;
+    let mut realised_yield = 0;
+    let mut performance_fee = 0;
+    if is_withdrawal {
+        underlying_amount = lp_to_underlying(
+            &ctx.accounts.vault,
+            amount_to_withdraw_from_mercurial,
+            ctx.accounts.lp_mint.supply,
+        )?;
+        (cost_basis, realised_yield) = ctx.accounts.holding_wallet_state.realise(
+            amount_to_withdraw_from_mercurial,
+            ctx.accounts.holding_wallet_lp_token_account.amount,
+            underlying_amount,
+        );
+        performance_fee = ctx.accounts.protocol_config.performance_fee(realised_yield);
+    } else {
+        let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+        holding_wallet_state.principal = holding_wallet_state
+            .principal
+            .checked_add(amount_to_deposit_to_mercurial)
+            .ok_or(NovaError::MathOverflow)?;
+    }
 
     let signer_seeds = &[
         b"holding-wallet".as_ref(),
-        ctx.accounts.employee.key.as_ref(),
+        ctx.accounts.payee.key.as_ref(),
+        _organisation_id.as_bytes(),
         &[ctx.accounts.holding_wallet_state.wallet_bump],
     ];
     let signer: &[&[&[u8]]; 1] = &[&signer_seeds[..]];
@@ -108,12 +228,36 @@ pub fn adjust_meteora_allocation(
         mecurial_accounts,
     ).with_signer(signer);
 
-    if amount_to_withdraw_from_mercurial > 0 && amount_to_deposit_to_mercurial == 0 {
+    if is_withdrawal {
         mercurial_vault::cpi::withdraw(
             mecurial_context,
             amount_to_withdraw_from_mercurial,
             0,
         )?;
+
+        if performance_fee > 0 {
+            let fee_accounts = Transfer {
+                from: ctx.accounts.holding_wallet_token_account.to_account_info(),
+                to: ctx.accounts.protocol_fee_token_account.to_account_info(),
+                authority: ctx.accounts.holding_wallet.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), fee_accounts)
+                    .with_signer(signer),
+                performance_fee,
+            )?;
+        }
+
+        emit!(YieldRealised {
+            payee: ctx.accounts.payee.key(),
+            organisation_id: _organisation_id,
+            lp_amount: amount_to_withdraw_from_mercurial,
+            underlying_amount,
+            cost_basis,
+            realised_yield,
+            performance_fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
     } else {
         mercurial_vault::cpi::deposit(
             mecurial_context,
@@ -125,12 +269,46 @@ pub fn adjust_meteora_allocation(
     Ok(())
 }
 
+pub fn get_holding_balance(
+    ctx: Context<GetHoldingBalance>,
+    _organisation_id: String,
+) -> Result<HoldingBalance> {
+    let idle_balance = ctx.accounts.holding_wallet_token_account.amount;
+    let lp_balance = ctx.accounts.holding_wallet_lp_token_account.amount;
+    let lp_underlying_value = lp_to_underlying(
+        &ctx.accounts.vault,
+        lp_balance,
+        ctx.accounts.lp_mint.supply,
+    )?;
+    let total = idle_balance
+        .checked_add(lp_underlying_value)
+        .ok_or(NovaError::MathOverflow)?;
+
+    Ok(HoldingBalance {
+        idle_balance,
+        lp_balance,
+        lp_underlying_value,
+        total,
+        meteora_allocation: ctx.accounts.holding_wallet_state.meteora_allocation,
+        holding_allocation: ctx.accounts.holding_wallet_state.holding_allocation,
+    })
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct HoldingBalance {
+    pub idle_balance: u64,
+    pub lp_balance: u64,
+    pub lp_underlying_value: u64,
+    pub total: u64,
+    pub meteora_allocation: u8,
+    pub holding_allocation: u8,
+}
 
 #[derive(Accounts)]
 #[instruction(_organisation_id: String)]
 pub struct SetupHoldingWallet<'info> {
     #[account(
-        seeds = [b"holding-wallet", employee.key().as_ref()],
+        seeds = [b"holding-wallet", employee.key().as_ref(), _organisation_id.as_bytes()],
         bump,
     )]
     /// CHECK: This is not dangerous because we don't read or write from this account
@@ -139,10 +317,15 @@ pub struct SetupHoldingWallet<'info> {
         init ,
         payer = employee,
         space = 8 + HoldingWalletState::SIZE,
-        seeds = [b"holding-state", employee.key().as_ref(), _organisation_id.as_bytes().as_ref()],
+        seeds = [b"holding-state", employee.key().as_ref(), _organisation_id.as_bytes()],
         bump
     )]
     pub holding_wallet_state: Account<'info, HoldingWalletState>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
     #[account(mut)]
     pub employee: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -153,24 +336,40 @@ pub struct SetupHoldingWallet<'info> {
 pub struct EmployeeWithdraw<'info> {
     #[account(
         mut,
-        seeds = [b"holding-state", withdrawer.key().as_ref(), _organisation_id.as_bytes().as_ref()],
+        seeds = [b"holding-state", payee.key().as_ref(), _organisation_id.as_bytes()],
         bump = holding_wallet_state.bump,
+        constraint = holding_wallet_state.can_withdraw(withdrawer.key) @ NovaError::NotHoldingWalletOwner,
     )]
-    pub holding_wallet_state: Account<'info, HoldingWalletState>,
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
     #[account(
         mut,
-        seeds = [b"holding-wallet", withdrawer.key().as_ref()],
+        seeds = [b"holding-wallet", payee.key().as_ref(), _organisation_id.as_bytes()],
         bump=holding_wallet_state.wallet_bump,
     )]
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub holding_wallet: AccountInfo<'info>,
-    #[account(mut)]
+    /// CHECK: This is not dangerous because it only seeds the holding wallet accounts
+    pub payee: AccountInfo<'info>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        mut,
+        constraint = holding_wallet_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_token_account.mint == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount,
+    )]
     pub holding_wallet_token_account: Account<'info, TokenAccount>,
     #[account(mut)]
     pub withdrawer: Signer<'info>,
     #[account(mut)]
     pub withdrawer_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = holding_wallet_lp_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_lp_token_account.mint == lp_mint.key() @ NovaError::NotHoldingWalletTokenAccount,
+    )]
     pub holding_wallet_lp_token_account: Account<'info, TokenAccount>,
     #[account(
         mut,
@@ -182,7 +381,19 @@ pub struct EmployeeWithdraw<'info> {
     pub token_vault: Account<'info, TokenAccount>,
     #[account(mut)]
     pub lp_mint: Account<'info, Mint>,
+    #[account(constraint = token_mint.key() == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount)]
     pub token_mint: Account<'info, Mint>,
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+    #[account(
+        mut,
+        constraint = protocol_fee_token_account.owner == protocol_config.fee_recipient @ NovaError::NotFeeRecipientTokenAccount,
+        constraint = protocol_fee_token_account.mint == token_mint.key() @ NovaError::NotFeeRecipientTokenAccount,
+    )]
+    pub protocol_fee_token_account: Box<Account<'info, TokenAccount>>,
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
@@ -194,22 +405,33 @@ pub struct EmployeeWithdraw<'info> {
 pub struct AdjustMeteoraAllocation<'info> {
     #[account(
         mut,
-        seeds = [b"holding-state", employee.key().as_ref(), _organisation_id.as_bytes().as_ref()],
+        seeds = [b"holding-state", payee.key().as_ref(), _organisation_id.as_bytes()],
         bump = holding_wallet_state.bump,
+        constraint = holding_wallet_state.owner == employee.key() @ NovaError::NotHoldingWalletOwner,
     )]
-    pub holding_wallet_state: Account<'info, HoldingWalletState>,
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
     #[account(
         mut,
-        seeds = [b"holding-wallet", employee.key().as_ref()],
+        seeds = [b"holding-wallet", payee.key().as_ref(), _organisation_id.as_bytes()],
         bump=holding_wallet_state.wallet_bump,
     )]
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub holding_wallet: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it only seeds the holding wallet accounts
+    pub payee: AccountInfo<'info>,
     #[account(mut)]
     pub employee: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = holding_wallet_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_token_account.mint == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount,
+    )]
     pub holding_wallet_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = holding_wallet_lp_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_lp_token_account.mint == lp_mint.key() @ NovaError::NotHoldingWalletTokenAccount,
+    )]
     pub holding_wallet_lp_token_account: Account<'info, TokenAccount>,
     #[account(
         mut,
@@ -221,8 +443,51 @@ pub struct AdjustMeteoraAllocation<'info> {
     pub token_vault: Account<'info, TokenAccount>,
     #[account(mut)]
     pub lp_mint: Account<'info, Mint>,
+    #[account(constraint = token_mint.key() == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount)]
     pub token_mint: Account<'info, Mint>,
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+    #[account(
+        mut,
+        constraint = protocol_fee_token_account.owner == protocol_config.fee_recipient @ NovaError::NotFeeRecipientTokenAccount,
+        constraint = protocol_fee_token_account.mint == token_mint.key() @ NovaError::NotFeeRecipientTokenAccount,
+    )]
+    pub protocol_fee_token_account: Box<Account<'info, TokenAccount>>,
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub token_program: Program<'info, Token>,
     pub mercurial_program: Program<'info, Vault>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct GetHoldingBalance<'info> {
+    #[account(
+        seeds = [b"holding-state", employee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.bump,
+    )]
+    pub holding_wallet_state: Account<'info, HoldingWalletState>,
+    #[account(
+        seeds = [b"holding-wallet", employee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub holding_wallet: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub employee: AccountInfo<'info>,
+    #[account(
+        constraint = holding_wallet_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_token_account.mint == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_token_account: Account<'info, TokenAccount>,
+    #[account(
+        constraint = holding_wallet_lp_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_lp_token_account.mint == lp_mint.key() @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_lp_token_account: Account<'info, TokenAccount>,
+    #[account(has_one = lp_mint)]
+    pub vault: Box<Account<'info, mercurial_vault::state::Vault>>,
+    pub lp_mint: Account<'info, Mint>,
 }
\ No newline at end of file
@@ -0,0 +1,276 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::events::{ExpenseClaimResolved, ExpenseClaimSubmitted};
+use crate::instructions::stream::verify_admin_approvals;
+use crate::state::{
+    ContractStatus, Currency, EmployeeContract, ExpenseCategory, ExpenseClaim, ExpenseClaimStatus,
+    Organisation, PaymentKind, PaymentReceipt,
+};
+use crate::token_interface::{is_token_program, token_account, transfer_fee};
+use crate::utils::transfer_from_streaming_wallet;
+
+pub fn submit_expense_claim(
+    ctx: Context<SubmitExpenseClaim>,
+    _organisation_id: String,
+    _employee_id: String,
+    receipt_hash: [u8; 32],
+    amount: u64,
+    category: ExpenseCategory,
+) -> Result<()> {
+    if ctx.accounts.employee_contract.status != ContractStatus::Active {
+        return err!(NovaError::ContractNotActive)
+    }
+    if amount == 0 {
+        return err!(NovaError::InvalidExpenseClaim)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let expense_claim = &mut ctx.accounts.expense_claim;
+    expense_claim.employee_contract = ctx.accounts.employee_contract.key();
+    expense_claim.claimant = ctx.accounts.payee.key();
+    expense_claim.amount = amount;
+    expense_claim.category = category;
+    expense_claim.receipt_hash = receipt_hash;
+    expense_claim.status = ExpenseClaimStatus::Submitted;
+    expense_claim.submitted_at = now;
+    expense_claim.resolved_at = 0;
+    expense_claim.resolver = Pubkey::default();
+    expense_claim.bump = *ctx.bumps.get("expense_claim").unwrap();
+
+    emit!(ExpenseClaimSubmitted {
+        expense_claim: expense_claim.key(),
+        employee_contract: expense_claim.employee_contract,
+        amount,
+        category,
+        receipt_hash,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Remaining accounts: the additional admin approvers required by the reimbursement payment policy
+pub fn approve_expense_claim(
+    ctx: Context<ResolveExpenseClaim>,
+    _organisation_id: String,
+    _employee_id: String,
+    _receipt_hash: [u8; 32],
+) -> Result<()> {
+    let organisation = &ctx.accounts.organisation;
+    if !organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.expense_claim.status != ExpenseClaimStatus::Submitted {
+        return err!(NovaError::ExpenseClaimNotPending)
+    }
+
+    let amount = ctx.accounts.expense_claim.amount;
+    let required_approvals = organisation.payment_policy(PaymentKind::Reimbursement).approvals_for(amount);
+    verify_admin_approvals(&mut ctx.remaining_accounts.iter(), organisation, required_approvals)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let expense_claim = &mut ctx.accounts.expense_claim;
+    expense_claim.status = ExpenseClaimStatus::Approved;
+    expense_claim.resolved_at = now;
+    expense_claim.resolver = ctx.accounts.admin.key();
+    emit!(ExpenseClaimResolved {
+        expense_claim: expense_claim.key(),
+        status: ExpenseClaimStatus::Approved,
+        amount,
+        resolver: expense_claim.resolver,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Reimburses an approved claim from the streaming wallet and writes a reimbursement receipt, the
+// claim amount is in the payout mint and paid gross
+pub fn pay_expense_claim(
+    ctx: Context<PayExpenseClaim>,
+    _organisation_id: String,
+    _employee_id: String,
+    _receipt_hash: [u8; 32],
+) -> Result<()> {
+    if ctx.accounts.organisation.stream_authority != ctx.accounts.payer.key() {
+        return err!(NovaError::PayerIsNotStreamAuthority)
+    }
+    if ctx.accounts.expense_claim.status != ExpenseClaimStatus::Approved {
+        return err!(NovaError::ExpenseClaimNotApproved)
+    }
+    if token_account(&ctx.accounts.claimant_token_account)?.owner != ctx.accounts.expense_claim.claimant {
+        return err!(NovaError::NotEmployeeTokenAccount)
+    }
+
+    let amount = ctx.accounts.expense_claim.amount;
+    transfer_from_streaming_wallet(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.streaming_wallet.to_account_info(),
+        ctx.accounts.streaming_wallet_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.claimant_token_account.to_account_info(),
+        &_organisation_id,
+        ctx.accounts.organisation.stream_wallet_bump,
+        amount,
+    )?;
+    let transfer_fee = transfer_fee(&ctx.accounts.mint, amount)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let employee_contract = &mut ctx.accounts.employee_contract;
+    let payment_index = employee_contract.payment_count;
+    employee_contract.payment_count += 1;
+
+    let receipt = &mut ctx.accounts.receipt;
+    receipt.employee_contract = employee_contract.key();
+    receipt.payment_index = payment_index;
+    receipt.kind = PaymentKind::Reimbursement;
+    receipt.gross_amount = amount;
+    receipt.total_deductions = 0;
+    receipt.employer_contributions = 0;
+    receipt.net_amount = amount - transfer_fee;
+    receipt.payer = ctx.accounts.payer.key();
+    receipt.paid_at = now;
+    receipt.bump = *ctx.bumps.get("receipt").unwrap();
+    receipt.currency = Currency::USD;
+    receipt.contract_amount = amount;
+    receipt.fx_price = 1;
+    receipt.fx_expo = 0;
+    receipt.transfer_fee = transfer_fee;
+
+    let expense_claim = &mut ctx.accounts.expense_claim;
+    expense_claim.status = ExpenseClaimStatus::Paid;
+    emit!(ExpenseClaimResolved {
+        expense_claim: expense_claim.key(),
+        status: ExpenseClaimStatus::Paid,
+        amount,
+        resolver: expense_claim.resolver,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn reject_expense_claim(
+    ctx: Context<ResolveExpenseClaim>,
+    _organisation_id: String,
+    _employee_id: String,
+    _receipt_hash: [u8; 32],
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.expense_claim.status != ExpenseClaimStatus::Submitted {
+        return err!(NovaError::ExpenseClaimNotPending)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let expense_claim = &mut ctx.accounts.expense_claim;
+    expense_claim.status = ExpenseClaimStatus::Rejected;
+    expense_claim.resolved_at = now;
+    expense_claim.resolver = ctx.accounts.admin.key();
+
+    emit!(ExpenseClaimResolved {
+        expense_claim: expense_claim.key(),
+        status: ExpenseClaimStatus::Rejected,
+        amount: expense_claim.amount,
+        resolver: expense_claim.resolver,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, receipt_hash: [u8; 32])]
+pub struct SubmitExpenseClaim<'info> {
+    #[account(
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+        constraint = employee_contract.payee == payee.key() @ NovaError::NotContractPayee,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        init,
+        payer = payee,
+        space = 8 + ExpenseClaim::SIZE,
+        seeds = [b"expense-claim", employee_contract.key().as_ref(), receipt_hash.as_ref()],
+        bump
+    )]
+    pub expense_claim: Box<Account<'info, ExpenseClaim>>,
+    #[account(mut)]
+    pub payee: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, _receipt_hash: [u8; 32])]
+pub struct ResolveExpenseClaim<'info> {
+    #[account(
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        mut,
+        seeds = [b"expense-claim", employee_contract.key().as_ref(), _receipt_hash.as_ref()],
+        bump = expense_claim.bump,
+    )]
+    pub expense_claim: Box<Account<'info, ExpenseClaim>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, _receipt_hash: [u8; 32])]
+pub struct PayExpenseClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        mut,
+        seeds = [b"expense-claim", employee_contract.key().as_ref(), _receipt_hash.as_ref()],
+        bump = expense_claim.bump,
+    )]
+    pub expense_claim: Box<Account<'info, ExpenseClaim>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        seeds = [b"streaming-wallet", _organisation_id.as_bytes()],
+        bump = organisation.stream_wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub streaming_wallet: AccountInfo<'info>,
+    #[account(mut)]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub streaming_wallet_token_account: AccountInfo<'info>,
+    #[account(mut)]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub claimant_token_account: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub mint: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PaymentReceipt::SIZE,
+        seeds = [b"payment-receipt", employee_contract.key().as_ref(), &employee_contract.payment_count.to_le_bytes()],
+        bump
+    )]
+    pub receipt: Box<Account<'info, PaymentReceipt>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(constraint = is_token_program(token_program.key) @ NovaError::InvalidTokenProgram)]
+    /// CHECK: This is not dangerous because it must be one of the token programs
+    pub token_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
\ No newline at end of file
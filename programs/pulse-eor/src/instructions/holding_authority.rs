@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::events::{HoldingWalletRecovered, HoldingWalletRecoveryCancelled, HoldingWalletRecoveryStarted};
+use crate::state::{HoldingWalletState, MAX_GUARDIANS, MIN_GUARDIAN_THRESHOLD, RECOVERY_DELAY};
+
+// Default `delegate` removes the delegate, its spending restarts with a fresh period
+pub fn set_holding_delegate(
+    ctx: Context<ManageHoldingAuthority>,
+    _organisation_id: String,
+    delegate: Pubkey,
+    delegate_limit: u64,
+    delegate_period: i64,
+) -> Result<()> {
+    if delegate != Pubkey::default() && delegate_period <= 0 {
+        return err!(NovaError::InvalidDelegate)
+    }
+
+    let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+    holding_wallet_state.delegate = delegate;
+    holding_wallet_state.delegate_limit = delegate_limit;
+    holding_wallet_state.delegate_period = delegate_period;
+    holding_wallet_state.delegate_period_start = Clock::get()?.unix_timestamp;
+    holding_wallet_state.delegate_spent = 0;
+
+    Ok(())
+}
+
+// An empty list with a zero threshold disables recovery, otherwise at least
+// `MIN_GUARDIAN_THRESHOLD` guardians must agree
+pub fn set_guardians(
+    ctx: Context<ManageHoldingAuthority>,
+    _organisation_id: String,
+    guardians: Vec<Pubkey>,
+    guardian_threshold: u8,
+) -> Result<()> {
+    if guardians.len() > MAX_GUARDIANS || guardian_threshold as usize > guardians.len() {
+        return err!(NovaError::InvalidGuardians)
+    }
+    if guardian_threshold < MIN_GUARDIAN_THRESHOLD && !guardians.is_empty() {
+        return err!(NovaError::InvalidGuardians)
+    }
+    if guardians.iter().enumerate().any(|(index, guardian)| guardians[..index].contains(guardian)) {
+        return err!(NovaError::InvalidGuardians)
+    }
+
+    let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+    holding_wallet_state.guardians = guardians;
+    holding_wallet_state.guardian_threshold = guardian_threshold;
+
+    Ok(())
+}
+
+// Starts moving ownership of the holding wallet to `new_owner`, which completes once
+// `RECOVERY_DELAY` has passed unless the current owner cancels it first. Remaining accounts: at
+// least `guardian_threshold` distinct guardian signers.
+pub fn recover_holding_wallet(
+    ctx: Context<RecoverHoldingWallet>,
+    _organisation_id: String,
+    new_owner: Pubkey,
+) -> Result<()> {
+    let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+    if holding_wallet_state.guardian_threshold == 0 || new_owner == Pubkey::default() {
+        return err!(NovaError::InvalidGuardians)
+    }
+    if holding_wallet_state.has_pending_recovery() {
+        return err!(NovaError::RecoveryPending)
+    }
+
+    let mut signed: Vec<Pubkey> = Vec::new();
+    for guardian in ctx.remaining_accounts.iter() {
+        if guardian.is_signer && holding_wallet_state.guardians.contains(guardian.key) && !signed.contains(guardian.key) {
+            signed.push(*guardian.key);
+        }
+    }
+    if signed.len() < holding_wallet_state.guardian_threshold as usize {
+        return err!(NovaError::MissingApprovals)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    holding_wallet_state.pending_owner = new_owner;
+    holding_wallet_state.recovery_available_at = now.saturating_add(RECOVERY_DELAY);
+
+    emit!(HoldingWalletRecoveryStarted {
+        holding_wallet_state: holding_wallet_state.key(),
+        new_owner,
+        guardians: signed,
+        available_at: holding_wallet_state.recovery_available_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Permissionless once the delay has passed, moves ownership of the organisation's holding wallet
+// to the pending owner and drops any delegate set by the previous owner, whose standing orders
+// lapse with it
+pub fn complete_holding_wallet_recovery(
+    ctx: Context<RecoverHoldingWallet>,
+    _organisation_id: String,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+    if !holding_wallet_state.has_pending_recovery() || now < holding_wallet_state.recovery_available_at {
+        return err!(NovaError::RecoveryNotReady)
+    }
+
+    let previous_owner = holding_wallet_state.owner;
+    let new_owner = holding_wallet_state.pending_owner;
+    holding_wallet_state.owner = new_owner;
+    holding_wallet_state.pending_owner = Pubkey::default();
+    holding_wallet_state.recovery_available_at = 0;
+    holding_wallet_state.delegate = Pubkey::default();
+    holding_wallet_state.delegate_limit = 0;
+    holding_wallet_state.delegate_spent = 0;
+
+    emit!(HoldingWalletRecovered {
+        holding_wallet_state: holding_wallet_state.key(),
+        previous_owner,
+        new_owner,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_holding_wallet_recovery(
+    ctx: Context<ManageHoldingAuthority>,
+    _organisation_id: String,
+) -> Result<()> {
+    let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+    if !holding_wallet_state.has_pending_recovery() {
+        return err!(NovaError::RecoveryNotReady)
+    }
+
+    let new_owner = holding_wallet_state.pending_owner;
+    holding_wallet_state.pending_owner = Pubkey::default();
+    holding_wallet_state.recovery_available_at = 0;
+
+    emit!(HoldingWalletRecoveryCancelled {
+        holding_wallet_state: holding_wallet_state.key(),
+        new_owner,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct ManageHoldingAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"holding-state", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.bump,
+        constraint = holding_wallet_state.owner == owner.key() @ NovaError::NotHoldingWalletOwner,
+    )]
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
+    /// CHECK: This is not dangerous because it only seeds the holding wallet accounts
+    pub payee: AccountInfo<'info>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct RecoverHoldingWallet<'info> {
+    #[account(
+        mut,
+        seeds = [b"holding-state", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.bump,
+    )]
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
+    /// CHECK: This is not dangerous because it only seeds the holding wallet accounts
+    pub payee: AccountInfo<'info>,
+}
\ No newline at end of file
@@ -0,0 +1,236 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::events::LeaveUpdated;
+use crate::state::{
+    leave_end, ContractStatus, EmployeeContract, LeaveRequest, LeaveRequestStatus, Organisation,
+    PayMode, LEAVE_UNITS_PER_DAY,
+};
+
+pub fn configure_leave(
+    ctx: Context<ConfigureLeave>,
+    _organisation_id: String,
+    _employee_id: String,
+    leave_accrual_rate: u64,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+
+    // Periods already completed accrue at the previous rate
+    let employee_contract = &mut ctx.accounts.employee_contract;
+    employee_contract.accrue_leave(Clock::get()?.unix_timestamp);
+    employee_contract.leave_accrual_rate = leave_accrual_rate;
+
+    Ok(())
+}
+
+pub fn request_leave(
+    ctx: Context<RequestLeave>,
+    _organisation_id: String,
+    _employee_id: String,
+    start_date: i64,
+    days: u16,
+) -> Result<()> {
+    if ctx.accounts.employee_contract.status != ContractStatus::Active {
+        return err!(NovaError::ContractNotActive)
+    }
+    if days == 0 {
+        return err!(NovaError::InvalidLeaveRequest)
+    }
+    if start_date < ctx.accounts.employee_contract.last_leave_end {
+        return err!(NovaError::OverlappingLeaveRequest)
+    }
+    ctx.accounts.employee_contract.last_leave_end = leave_end(start_date, days);
+
+    let now = Clock::get()?.unix_timestamp;
+    let leave_request = &mut ctx.accounts.leave_request;
+    leave_request.employee_contract = ctx.accounts.employee_contract.key();
+    leave_request.start_date = start_date;
+    leave_request.days = days;
+    leave_request.status = LeaveRequestStatus::Requested;
+    leave_request.paid_units = 0;
+    leave_request.unpaid_units = 0;
+    leave_request.approver = Pubkey::default();
+    leave_request.requested_at = now;
+    leave_request.bump = *ctx.bumps.get("leave_request").unwrap();
+
+    emit!(LeaveUpdated {
+        leave_request: leave_request.key(),
+        employee_contract: leave_request.employee_contract,
+        status: LeaveRequestStatus::Requested,
+        days,
+        unpaid_units: 0,
+        leave_balance: ctx.accounts.employee_contract.leave_balance,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Approved leave is drawn from the leave balance, any days beyond it are unpaid and withheld
+// from the next salary payment. Hourly workers are only paid for the hours on their
+// timesheets, so their unpaid days are recorded on the request but never withheld.
+pub fn approve_leave(
+    ctx: Context<ApproveLeave>,
+    _organisation_id: String,
+    _employee_id: String,
+    _start_date: i64,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.employee_contract.status != ContractStatus::Active {
+        return err!(NovaError::ContractNotActive)
+    }
+    if ctx.accounts.leave_request.status != LeaveRequestStatus::Requested {
+        return err!(NovaError::InvalidLeaveRequest)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let employee_contract = &mut ctx.accounts.employee_contract;
+    employee_contract.accrue_leave(now);
+
+    let leave_request = &mut ctx.accounts.leave_request;
+    let units = leave_request.days as u64 * LEAVE_UNITS_PER_DAY;
+    let paid_units = units.min(employee_contract.leave_balance);
+    let unpaid_units = units - paid_units;
+    employee_contract.leave_balance -= paid_units;
+    if employee_contract.pay_mode == PayMode::Salaried {
+        employee_contract.unpaid_leave = employee_contract.unpaid_leave.saturating_add(unpaid_units);
+    }
+
+    leave_request.status = LeaveRequestStatus::Approved;
+    leave_request.paid_units = paid_units;
+    leave_request.unpaid_units = unpaid_units;
+    leave_request.approver = ctx.accounts.admin.key();
+
+    emit!(LeaveUpdated {
+        leave_request: leave_request.key(),
+        employee_contract: leave_request.employee_contract,
+        status: LeaveRequestStatus::Approved,
+        days: leave_request.days,
+        unpaid_units,
+        leave_balance: employee_contract.leave_balance,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Rejected requests are closed so the employee can request the dates again, which reopens them
+// for requests when they are the latest ones
+pub fn reject_leave(
+    ctx: Context<RejectLeave>,
+    _organisation_id: String,
+    _employee_id: String,
+    _start_date: i64,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.leave_request.status != LeaveRequestStatus::Requested {
+        return err!(NovaError::InvalidLeaveRequest)
+    }
+
+    let leave_request = &ctx.accounts.leave_request;
+    let employee_contract = &mut ctx.accounts.employee_contract;
+    if employee_contract.last_leave_end == leave_request.end_date() {
+        employee_contract.last_leave_end = leave_request.start_date;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct ConfigureLeave<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, start_date: i64)]
+pub struct RequestLeave<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+        constraint = employee_contract.payee == payee.key() @ NovaError::NotContractPayee,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        init,
+        payer = payee,
+        space = 8 + LeaveRequest::SIZE,
+        seeds = [b"leave-request", employee_contract.key().as_ref(), &start_date.to_le_bytes()],
+        bump
+    )]
+    pub leave_request: Box<Account<'info, LeaveRequest>>,
+    #[account(mut)]
+    pub payee: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, _start_date: i64)]
+pub struct ApproveLeave<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        mut,
+        seeds = [b"leave-request", employee_contract.key().as_ref(), &_start_date.to_le_bytes()],
+        bump = leave_request.bump,
+    )]
+    pub leave_request: Box<Account<'info, LeaveRequest>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, _start_date: i64)]
+pub struct RejectLeave<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        mut,
+        close = payee,
+        seeds = [b"leave-request", employee_contract.key().as_ref(), &_start_date.to_le_bytes()],
+        bump = leave_request.bump,
+    )]
+    pub leave_request: Box<Account<'info, LeaveRequest>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        mut,
+        constraint = payee.key() == employee_contract.payee @ NovaError::NotContractPayee,
+    )]
+    /// CHECK: This is not dangerous because we only refund rent to the contract payee
+    pub payee: AccountInfo<'info>,
+    pub admin: Signer<'info>,
+}
\ No newline at end of file
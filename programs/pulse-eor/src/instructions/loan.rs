@@ -0,0 +1,343 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::events::{LoanDefaulted, LoanIssued, LoanRepayment};
+use crate::state::{ContractStatus, EmployeeContract, LoanAccount, LoanStatus, Organisation};
+use crate::token_interface::{is_token_program, token_account, transfer_checked};
+use crate::utils::{bps_of, transfer_from_streaming_wallet};
+
+pub fn issue_loan(
+    ctx: Context<IssueLoan>,
+    _organisation_id: String,
+    _employee_id: String,
+    principal: u64,
+    interest_rate_bps: u16,
+    term: u16,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.employee_contract.status != ContractStatus::Active {
+        return err!(NovaError::ContractNotActive)
+    }
+    if ctx.accounts.employee_contract.has_loan() {
+        return err!(NovaError::LoanAlreadyActive)
+    }
+    if principal == 0 || term == 0 {
+        return err!(NovaError::InvalidLoanTerms)
+    }
+
+    let total_due = principal
+        .checked_add(bps_of(principal, interest_rate_bps))
+        .ok_or(NovaError::MathOverflow)?;
+    let installment_amount = total_due / term as u64 + u64::from(total_due % term as u64 != 0);
+
+    let loan = &mut ctx.accounts.loan;
+    loan.employee_contract = ctx.accounts.employee_contract.key();
+    loan.lender_token_account = ctx.accounts.lender_token_account.key();
+    loan.principal = principal;
+    loan.interest_rate_bps = interest_rate_bps;
+    loan.term = term;
+    loan.installment_amount = installment_amount;
+    loan.remaining_balance = total_due;
+    loan.installments_paid = 0;
+    loan.status = LoanStatus::Active;
+    loan.issued_at = Clock::get()?.unix_timestamp;
+    loan.bump = *ctx.bumps.get("loan").unwrap();
+
+    ctx.accounts.employee_contract.loan = ctx.accounts.loan.key();
+
+    transfer_checked(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.admin_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.employee_token_account.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        &[],
+        principal,
+    )?;
+
+    emit!(LoanIssued {
+        loan: ctx.accounts.loan.key(),
+        employee_contract: ctx.accounts.employee_contract.key(),
+        principal,
+        interest_rate_bps,
+        term,
+        installment_amount,
+        total_due,
+    });
+
+    Ok(())
+}
+
+pub fn repay_loan(
+    ctx: Context<RepayLoan>,
+    _organisation_id: String,
+    _employee_id: String,
+    amount: u64,
+) -> Result<()> {
+    if ctx.accounts.loan.status != LoanStatus::Active {
+        return err!(NovaError::LoanNotActive)
+    }
+
+    let repaid = ctx.accounts.loan.apply_repayment(amount);
+    if ctx.accounts.loan.status == LoanStatus::Repaid {
+        ctx.accounts.employee_contract.loan = Pubkey::default();
+    }
+
+    transfer_checked(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.payee_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.lender_token_account.to_account_info(),
+        ctx.accounts.payee.to_account_info(),
+        &[],
+        repaid,
+    )?;
+
+    emit!(LoanRepayment {
+        loan: ctx.accounts.loan.key(),
+        amount: repaid,
+        remaining_balance: ctx.accounts.loan.remaining_balance,
+        early: true,
+    });
+
+    Ok(())
+}
+
+pub fn default_loan(
+    ctx: Context<ManageLoan>,
+    _organisation_id: String,
+    _employee_id: String,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.loan.status != LoanStatus::Active {
+        return err!(NovaError::LoanNotActive)
+    }
+
+    write_off_loan(&mut ctx.accounts.loan, &mut ctx.accounts.employee_contract);
+
+    Ok(())
+}
+
+pub fn close_loan(
+    ctx: Context<CloseLoan>,
+    _organisation_id: String,
+    _employee_id: String,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.loan.status == LoanStatus::Active {
+        return err!(NovaError::LoanAlreadyActive)
+    }
+
+    Ok(())
+}
+
+pub fn write_off_loan(loan: &mut Account<LoanAccount>, employee_contract: &mut EmployeeContract) {
+    loan.status = LoanStatus::Defaulted;
+    employee_contract.loan = Pubkey::default();
+
+    emit!(LoanDefaulted {
+        loan: loan.key(),
+        employee_contract: loan.employee_contract,
+        remaining_balance: loan.remaining_balance,
+    });
+}
+
+// Diverts the scheduled installment of the contract's active loan, or its whole outstanding
+// balance when `settle_in_full`, capped at `available`, from the streaming wallet to the lender.
+// Expects the loan account and the lender token account as the next two remaining accounts
+// when the contract carries a loan.
+#[allow(clippy::too_many_arguments)]
+pub fn collect_loan_installment<'info>(
+    remaining_accounts: &mut std::slice::Iter<AccountInfo<'info>>,
+    employee_contract: &mut EmployeeContract,
+    token_program: AccountInfo<'info>,
+    streaming_wallet: AccountInfo<'info>,
+    streaming_wallet_token_account: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    organisation_id: &str,
+    stream_wallet_bump: u8,
+    available: u64,
+    settle_in_full: bool,
+) -> Result<u64> {
+    if !employee_contract.has_loan() {
+        return Ok(0)
+    }
+
+    let loan_info = remaining_accounts.next().ok_or(NovaError::MissingLoanAccounts)?;
+    let lender_token_account = remaining_accounts.next().ok_or(NovaError::MissingLoanAccounts)?;
+    if loan_info.key() != employee_contract.loan {
+        return err!(NovaError::InvalidLoanAccount)
+    }
+
+    let mut loan: Account<LoanAccount> = Account::try_from(loan_info)?;
+    if lender_token_account.key() != loan.lender_token_account {
+        return err!(NovaError::InvalidLoanAccount)
+    }
+
+    let due = if settle_in_full {
+        loan.remaining_balance
+    } else {
+        loan.installment_amount
+    };
+    let installment = due.min(available);
+    let repaid = loan.apply_repayment(installment);
+    if repaid > 0 {
+        loan.installments_paid += 1;
+    }
+    if loan.status == LoanStatus::Repaid {
+        employee_contract.loan = Pubkey::default();
+    }
+
+    if repaid > 0 {
+        transfer_from_streaming_wallet(
+            token_program,
+            streaming_wallet,
+            streaming_wallet_token_account,
+            mint,
+            lender_token_account.clone(),
+            organisation_id,
+            stream_wallet_bump,
+            repaid,
+        )?;
+    }
+    loan.exit(&crate::ID)?;
+
+    emit!(LoanRepayment {
+        loan: loan.key(),
+        amount: repaid,
+        remaining_balance: loan.remaining_balance,
+        early: false,
+    });
+
+    Ok(repaid)
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct IssueLoan<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + LoanAccount::SIZE,
+        seeds = [b"loan", employee_contract.key().as_ref()],
+        bump
+    )]
+    pub loan: Box<Account<'info, LoanAccount>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub admin_token_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_account(&employee_token_account)?.owner == employee_contract.payee @ NovaError::NotEmployeeTokenAccount,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub employee_token_account: AccountInfo<'info>,
+    #[account(
+        constraint = token_account(&lender_token_account)?.mint == token_account(&admin_token_account)?.mint @ NovaError::InvalidLoanAccount,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub lender_token_account: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key) @ NovaError::InvalidTokenProgram)]
+    /// CHECK: This is not dangerous because it must be one of the token programs
+    pub token_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct RepayLoan<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+        constraint = employee_contract.payee == payee.key() @ NovaError::NotContractPayee,
+    )]
+    pub employee_contract: Account<'info, EmployeeContract>,
+    #[account(
+        mut,
+        seeds = [b"loan", employee_contract.key().as_ref()],
+        bump = loan.bump,
+        has_one = lender_token_account,
+    )]
+    pub loan: Account<'info, LoanAccount>,
+    #[account(mut)]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub lender_token_account: AccountInfo<'info>,
+    pub payee: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub payee_token_account: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key) @ NovaError::InvalidTokenProgram)]
+    /// CHECK: This is not dangerous because it must be one of the token programs
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct ManageLoan<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Account<'info, EmployeeContract>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        mut,
+        seeds = [b"loan", employee_contract.key().as_ref()],
+        bump = loan.bump,
+    )]
+    pub loan: Account<'info, LoanAccount>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct CloseLoan<'info> {
+    #[account(
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Account<'info, EmployeeContract>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"loan", employee_contract.key().as_ref()],
+        bump = loan.bump,
+    )]
+    pub loan: Account<'info, LoanAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
\ No newline at end of file
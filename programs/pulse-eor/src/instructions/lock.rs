@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::error::NovaError;
+use crate::events::LockUpdated;
+use crate::state::{HoldingWalletState, LockBucket, Organisation, MAX_LOCK_BUCKETS, MAX_LOCK_NAME_LEN};
+use crate::utils::{bps_of, lp_to_underlying};
+
+// Sets aside `amount` of the holding wallet balance, idle or in the Meteora vault, until
+// `unlock_at`. Bucket names are unique within the holding wallet.
+pub fn lock_funds(
+    ctx: Context<LockFunds>,
+    _organisation_id: String,
+    name: String,
+    amount: u64,
+    unlock_at: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let holding_wallet_state = &ctx.accounts.holding_wallet_state;
+    if name.is_empty() || name.len() > MAX_LOCK_NAME_LEN || amount == 0 || unlock_at <= now {
+        return err!(NovaError::InvalidLockBucket)
+    }
+    if holding_wallet_state.locks.len() >= MAX_LOCK_BUCKETS
+        || holding_wallet_state.locks.iter().any(|bucket| bucket.name == name)
+    {
+        return err!(NovaError::InvalidLockBucket)
+    }
+
+    let lp_value = lp_to_underlying(
+        &ctx.accounts.vault,
+        ctx.accounts.holding_wallet_lp_token_account.amount,
+        ctx.accounts.lp_mint.supply,
+    )?;
+    let balance = ctx.accounts.holding_wallet_token_account.amount.saturating_add(lp_value);
+    holding_wallet_state.ensure_unlocked(balance, amount, now)?;
+
+    let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+    holding_wallet_state.locks.push(LockBucket {
+        name: name.clone(),
+        amount,
+        unlock_at,
+    });
+
+    emit!(LockUpdated {
+        holding_wallet_state: holding_wallet_state.key(),
+        name,
+        amount,
+        unlock_at,
+        released: false,
+        penalty: 0,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Removes an expired bucket, or one still locked when the organisation allows early unlocks, in
+// which case the organisation's penalty on the bucket amount is paid out of the idle balance to
+// the streaming wallet
+pub fn release_lock(
+    ctx: Context<ReleaseLock>,
+    _organisation_id: String,
+    name: String,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+    let index = holding_wallet_state
+        .locks
+        .iter()
+        .position(|bucket| bucket.name == name)
+        .ok_or(NovaError::InvalidLockBucket)?;
+    let bucket = holding_wallet_state.locks.remove(index);
+
+    let mut penalty = 0;
+    if bucket.unlock_at > now {
+        let organisation = &ctx.accounts.organisation;
+        if !organisation.early_unlock_allowed {
+            return err!(NovaError::FundsLocked)
+        }
+        penalty = bps_of(bucket.amount, organisation.early_unlock_penalty_bps);
+    }
+
+    if penalty > 0 {
+        let signer_seeds = &[
+            b"holding-wallet".as_ref(),
+            ctx.accounts.payee.key.as_ref(),
+            _organisation_id.as_bytes(),
+            &[ctx.accounts.holding_wallet_state.wallet_bump],
+        ];
+        let penalty_accounts = Transfer {
+            from: ctx.accounts.holding_wallet_token_account.to_account_info(),
+            to: ctx.accounts.streaming_wallet_token_account.to_account_info(),
+            authority: ctx.accounts.holding_wallet.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), penalty_accounts)
+                .with_signer(&[&signer_seeds[..]]),
+            penalty,
+        )?;
+    }
+
+    emit!(LockUpdated {
+        holding_wallet_state: ctx.accounts.holding_wallet_state.key(),
+        name: bucket.name,
+        amount: bucket.amount,
+        unlock_at: bucket.unlock_at,
+        released: true,
+        penalty,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct LockFunds<'info> {
+    #[account(
+        mut,
+        seeds = [b"holding-state", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.bump,
+        constraint = holding_wallet_state.owner == employee.key() @ NovaError::NotHoldingWalletOwner,
+    )]
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
+    #[account(
+        seeds = [b"holding-wallet", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub holding_wallet: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it only seeds the holding wallet accounts
+    pub payee: AccountInfo<'info>,
+    #[account(
+        constraint = holding_wallet_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_token_account.mint == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = holding_wallet_lp_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_lp_token_account.mint == lp_mint.key() @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_lp_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(has_one = lp_mint)]
+    pub vault: Box<Account<'info, mercurial_vault::state::Vault>>,
+    pub lp_mint: Box<Account<'info, Mint>>,
+    pub employee: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct ReleaseLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"holding-state", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.bump,
+        constraint = holding_wallet_state.owner == employee.key() @ NovaError::NotHoldingWalletOwner,
+    )]
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
+    #[account(
+        seeds = [b"holding-wallet", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub holding_wallet: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it only seeds the holding wallet accounts
+    pub payee: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = holding_wallet_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        seeds = [b"streaming-wallet", _organisation_id.as_bytes()],
+        bump = organisation.stream_wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub streaming_wallet: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = streaming_wallet_token_account.owner == streaming_wallet.key() @ NovaError::InvalidLockBucket,
+        constraint = streaming_wallet_token_account.mint == holding_wallet_token_account.mint @ NovaError::InvalidLockBucket,
+    )]
+    pub streaming_wallet_token_account: Box<Account<'info, TokenAccount>>,
+    pub employee: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
\ No newline at end of file
@@ -0,0 +1,370 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::error::NovaError;
+use crate::events::{AccountMigrated, LegacyHoldingWalletSwept};
+use crate::state::{EmployeeContract, HoldingWalletState, Organisation, ProtocolConfig};
+use crate::utils::lp_to_underlying;
+
+// Migrations grow the account to the current layout and backfill defaults, with `payer` covering
+// the extra rent. They are permissionless except for employee contracts, whose pay period may
+// have to be supplied by an admin.
+
+pub fn migrate_organisation(ctx: Context<MigrateAccount>) -> Result<()> {
+    // Admins lead the layout, the account is sized by their count
+    let admin_count = ctx.accounts.account
+        .try_borrow_data()?
+        .get(8..12)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+        .ok_or(NovaError::InvalidMigration)?;
+    let (mut organisation, unversioned) = grow_account::<Organisation>(
+        &ctx.accounts.account,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        Organisation::SIZE + 32 * admin_count,
+    )?;
+    if unversioned {
+        organisation.version = 0;
+        organisation.reserved = [0; 64];
+    }
+    if organisation.version >= Organisation::VERSION {
+        return err!(NovaError::AlreadyMigrated)
+    }
+
+    let from_version = organisation.version;
+    organisation.migrate();
+    organisation.exit(&crate::ID)?;
+
+    emit!(AccountMigrated {
+        account: organisation.key(),
+        from_version,
+        to_version: organisation.version,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Contracts written before the pay period was stored only hold their payee, bump and rate, they
+// take `pay_period` and accrue from now. The organisation must have been migrated first.
+pub fn migrate_employee_contract(
+    ctx: Context<MigrateEmployeeContract>,
+    _organisation_id: String,
+    _employee_id: String,
+    pay_period: i64,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+
+    let (mut employee_contract, unversioned) = grow_account::<EmployeeContract>(
+        &ctx.accounts.employee_contract,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        EmployeeContract::SIZE,
+    )?;
+    if employee_contract.pay_period <= 0 && pay_period <= 0 {
+        return err!(NovaError::InvalidPayPeriod)
+    }
+    if unversioned {
+        employee_contract.version = 0;
+        employee_contract.reserved = [0; 64];
+    }
+    if employee_contract.version >= EmployeeContract::VERSION {
+        return err!(NovaError::AlreadyMigrated)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let from_version = employee_contract.version;
+    employee_contract.migrate(&_organisation_id, pay_period, now);
+    employee_contract.exit(&crate::ID)?;
+
+    emit!(AccountMigrated {
+        account: employee_contract.key(),
+        from_version,
+        to_version: employee_contract.version,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Holding wallets used to be shared by all of a payee's organisations, migrated states move to the
+// organisation's own wallet and `sweep_legacy_holding_wallet` moves the shared wallet's funds there
+pub fn migrate_holding_wallet_state(
+    ctx: Context<MigrateHoldingWalletState>,
+    _organisation_id: String,
+) -> Result<()> {
+    let (mut holding_wallet_state, unversioned) = grow_account::<HoldingWalletState>(
+        &ctx.accounts.holding_wallet_state,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        HoldingWalletState::SIZE,
+    )?;
+    if unversioned {
+        holding_wallet_state.pending_owner = Pubkey::default();
+        holding_wallet_state.recovery_available_at = 0;
+        holding_wallet_state.version = 0;
+        holding_wallet_state.reserved = [0; 64];
+    }
+    if holding_wallet_state.version >= HoldingWalletState::VERSION {
+        return err!(NovaError::AlreadyMigrated)
+    }
+
+    let from_version = holding_wallet_state.version;
+    holding_wallet_state.migrate(*ctx.bumps.get("holding_wallet").unwrap());
+    holding_wallet_state.exit(&crate::ID)?;
+
+    emit!(AccountMigrated {
+        account: holding_wallet_state.key(),
+        from_version,
+        to_version: holding_wallet_state.version,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Moves the idle balance and LP of the holding wallet the payee's organisations used to share into
+// the organisation's own holding wallet. The shared wallet only ever held the payee's own pay, so
+// its owner can sweep all of it into any of their organisations' wallets. Its LP joins the
+// principal at its current value, the yield it earned before was never tracked and isn't charged
+// the performance fee.
+pub fn sweep_legacy_holding_wallet(
+    ctx: Context<SweepLegacyHoldingWallet>,
+    _organisation_id: String,
+) -> Result<()> {
+    let idle_amount = ctx.accounts.legacy_token_account.amount;
+    let lp_amount = ctx.accounts.legacy_lp_token_account.amount;
+    if idle_amount == 0 && lp_amount == 0 {
+        return err!(NovaError::NotEnoughBalanceError)
+    }
+
+    let payee = ctx.accounts.payee.key();
+    let legacy_bump = *ctx.bumps.get("legacy_holding_wallet").unwrap();
+    let signer_seeds = &[
+        b"holding-wallet".as_ref(),
+        payee.as_ref(),
+        &[legacy_bump],
+    ];
+    let signer: &[&[&[u8]]; 1] = &[&signer_seeds[..]];
+
+    if idle_amount > 0 {
+        let transfer_accounts = Transfer {
+            from: ctx.accounts.legacy_token_account.to_account_info(),
+            to: ctx.accounts.holding_wallet_token_account.to_account_info(),
+            authority: ctx.accounts.legacy_holding_wallet.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_accounts)
+                .with_signer(signer),
+            idle_amount,
+        )?;
+    }
+
+    let lp_value = lp_to_underlying(&ctx.accounts.vault, lp_amount, ctx.accounts.lp_mint.supply)?;
+    if lp_amount > 0 {
+        let transfer_accounts = Transfer {
+            from: ctx.accounts.legacy_lp_token_account.to_account_info(),
+            to: ctx.accounts.holding_wallet_lp_token_account.to_account_info(),
+            authority: ctx.accounts.legacy_holding_wallet.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_accounts)
+                .with_signer(signer),
+            lp_amount,
+        )?;
+
+        let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+        holding_wallet_state.principal = holding_wallet_state
+            .principal
+            .checked_add(lp_value)
+            .ok_or(NovaError::MathOverflow)?;
+    }
+
+    emit!(LegacyHoldingWalletSwept {
+        holding_wallet_state: ctx.accounts.holding_wallet_state.key(),
+        idle_amount,
+        lp_amount,
+        lp_value,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn migrate_protocol_config(ctx: Context<MigrateAccount>) -> Result<()> {
+    let (mut protocol_config, unversioned) = grow_account::<ProtocolConfig>(
+        &ctx.accounts.account,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        ProtocolConfig::SIZE,
+    )?;
+    if unversioned {
+        protocol_config.version = 0;
+        protocol_config.reserved = [0; 64];
+    }
+    if protocol_config.version >= ProtocolConfig::VERSION {
+        return err!(NovaError::AlreadyMigrated)
+    }
+
+    let from_version = protocol_config.version;
+    protocol_config.migrate();
+    protocol_config.exit(&crate::ID)?;
+
+    emit!(AccountMigrated {
+        account: protocol_config.key(),
+        from_version,
+        to_version: protocol_config.version,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Grows a `T` account to its current size and loads it, the added bytes read as zero. Accounts
+// written before versioning come back flagged, whatever a shrunk Vec left after their last field
+// is stale so their version and reserved bytes can't be trusted.
+fn grow_account<'info, T>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    size: usize,
+) -> Result<(Account<'info, T>, bool)>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Discriminator + Clone,
+{
+    let discriminator = T::discriminator();
+    if account.owner != &crate::ID || account.try_borrow_data()?.get(..8) != Some(&discriminator[..]) {
+        return err!(NovaError::InvalidMigration)
+    }
+
+    let space = 8 + size;
+    let unversioned = account.data_len() < space;
+    if unversioned {
+        let lamports = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+        if lamports > 0 {
+            let transfer_accounts = system_program::Transfer {
+                from: payer.to_account_info(),
+                to: account.clone(),
+            };
+            system_program::transfer(
+                CpiContext::new(system_program.to_account_info(), transfer_accounts),
+                lamports,
+            )?;
+        }
+        account.realloc(space, true)?;
+    }
+
+    Ok((Account::try_from(account)?, unversioned))
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut)]
+    /// CHECK: This is not dangerous because its owner and discriminator are checked before it is migrated
+    pub account: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct MigrateEmployeeContract<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because its owner and discriminator are checked before it is migrated
+    pub employee_contract: AccountInfo<'info>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct MigrateHoldingWalletState<'info> {
+    #[account(
+        mut,
+        seeds = [b"holding-state", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because its owner and discriminator are checked before it is migrated
+    pub holding_wallet_state: AccountInfo<'info>,
+    #[account(
+        seeds = [b"holding-wallet", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub holding_wallet: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it only seeds the holding wallet accounts
+    pub payee: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct SweepLegacyHoldingWallet<'info> {
+    #[account(
+        mut,
+        seeds = [b"holding-state", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.bump,
+        constraint = holding_wallet_state.owner == owner.key() @ NovaError::NotHoldingWalletOwner,
+    )]
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
+    #[account(
+        seeds = [b"holding-wallet", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub holding_wallet: AccountInfo<'info>,
+    #[account(
+        seeds = [b"holding-wallet", payee.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub legacy_holding_wallet: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it only seeds the holding wallet accounts
+    pub payee: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = legacy_token_account.owner == legacy_holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = legacy_token_account.mint == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub legacy_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = legacy_lp_token_account.owner == legacy_holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = legacy_lp_token_account.mint == lp_mint.key() @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub legacy_lp_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = holding_wallet_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_token_account.mint == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = holding_wallet_lp_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_lp_token_account.mint == lp_mint.key() @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_lp_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(has_one = lp_mint)]
+    pub vault: Box<Account<'info, mercurial_vault::state::Vault>>,
+    pub lp_mint: Box<Account<'info, Mint>>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
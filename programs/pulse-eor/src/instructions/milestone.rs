@@ -0,0 +1,458 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::events::MilestoneUpdated;
+use crate::state::{
+    Milestone, MilestoneContract, MilestoneContractStatus, MilestoneStatus, Organisation,
+    MAX_MILESTONES,
+};
+use crate::token_interface::{
+    create_token_account, gross_up_transfer_fee, is_token_program, token_account, transfer_checked,
+};
+use crate::utils::transfer_from_streaming_wallet;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct MilestoneArgs {
+    pub amount: u64,
+    pub due_date: i64,
+}
+
+pub fn setup_milestone_contract(
+    ctx: Context<SetupMilestoneContract>,
+    _organisation_id: String,
+    _contract_id: String,
+    milestones: Vec<MilestoneArgs>,
+    dispute_window: i64,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if milestones.is_empty() || milestones.len() > MAX_MILESTONES || dispute_window < 0 {
+        return err!(NovaError::InvalidMilestones)
+    }
+
+    let mut total_amount: u64 = 0;
+    for milestone in milestones.iter() {
+        total_amount = total_amount.checked_add(milestone.amount).ok_or(NovaError::MathOverflow)?;
+    }
+    // The escrow is funded net of the mint's transfer fee, so the organisation covers the fee
+    // and every milestone can be released in full
+    let funding_amount = gross_up_transfer_fee(&ctx.accounts.mint, total_amount)?;
+    if token_account(&ctx.accounts.streaming_wallet_token_account)?.amount < funding_amount {
+        return err!(NovaError::NotEnoughBalanceError)
+    }
+
+    let milestone_contract = &mut ctx.accounts.milestone_contract;
+    milestone_contract.contractor = ctx.accounts.contractor.key();
+    milestone_contract.mint = ctx.accounts.mint.key();
+    milestone_contract.dispute_window = dispute_window;
+    milestone_contract.total_amount = total_amount;
+    milestone_contract.released_amount = 0;
+    milestone_contract.status = MilestoneContractStatus::Active;
+    milestone_contract.bump = *ctx.bumps.get("milestone_contract").unwrap();
+    milestone_contract.escrow_bump = *ctx.bumps.get("escrow").unwrap();
+    milestone_contract.milestones = milestones
+        .iter()
+        .map(|milestone| Milestone {
+            amount: milestone.amount,
+            due_date: milestone.due_date,
+            status: MilestoneStatus::Pending,
+            submitted_at: 0,
+        })
+        .collect();
+
+    let milestone_contract_key = ctx.accounts.milestone_contract.key();
+    let escrow_seeds = &[
+        b"milestone-escrow".as_ref(),
+        milestone_contract_key.as_ref(),
+        &[ctx.accounts.milestone_contract.escrow_bump],
+    ];
+    create_token_account(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.escrow.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        &milestone_contract_key,
+        &[&escrow_seeds[..]],
+    )?;
+
+    transfer_from_streaming_wallet(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.streaming_wallet.to_account_info(),
+        ctx.accounts.streaming_wallet_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.escrow.to_account_info(),
+        &_organisation_id,
+        ctx.accounts.organisation.stream_wallet_bump,
+        funding_amount,
+    )?;
+
+    Ok(())
+}
+
+pub fn submit_milestone(
+    ctx: Context<SubmitMilestone>,
+    _organisation_id: String,
+    _contract_id: String,
+    index: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let milestone_contract = &mut ctx.accounts.milestone_contract;
+    if milestone_contract.status != MilestoneContractStatus::Active {
+        return err!(NovaError::ContractNotActive)
+    }
+    let milestone = milestone_contract
+        .milestones
+        .get_mut(index as usize)
+        .ok_or(NovaError::InvalidMilestones)?;
+    if milestone.status != MilestoneStatus::Pending {
+        return err!(NovaError::InvalidMilestoneStatus)
+    }
+    milestone.status = MilestoneStatus::Submitted;
+    milestone.submitted_at = now;
+
+    let amount = milestone.amount;
+
+    emit!(MilestoneUpdated {
+        milestone_contract: milestone_contract.key(),
+        index,
+        status: MilestoneStatus::Submitted,
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn dispute_milestone(
+    ctx: Context<ManageMilestone>,
+    _organisation_id: String,
+    _contract_id: String,
+    index: u8,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let milestone_contract = &mut ctx.accounts.milestone_contract;
+    let milestone = milestone_contract
+        .milestones
+        .get_mut(index as usize)
+        .ok_or(NovaError::InvalidMilestones)?;
+    if milestone.status != MilestoneStatus::Submitted {
+        return err!(NovaError::InvalidMilestoneStatus)
+    }
+    milestone.status = MilestoneStatus::Disputed;
+
+    let amount = milestone.amount;
+
+    emit!(MilestoneUpdated {
+        milestone_contract: milestone_contract.key(),
+        index,
+        status: MilestoneStatus::Disputed,
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn approve_milestone(
+    ctx: Context<ReleaseMilestone>,
+    _organisation_id: String,
+    _contract_id: String,
+    index: u8,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.authority.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+
+    let status = ctx
+        .accounts
+        .milestone_contract
+        .milestones
+        .get(index as usize)
+        .ok_or(NovaError::InvalidMilestones)?
+        .status;
+    if !matches!(status, MilestoneStatus::Pending | MilestoneStatus::Submitted | MilestoneStatus::Disputed) {
+        return err!(NovaError::InvalidMilestoneStatus)
+    }
+
+    release_milestone(ctx, _organisation_id, _contract_id, index)
+}
+
+// Releases a submitted milestone without an admin once its dispute window has passed and it is due
+pub fn auto_release_milestone(
+    ctx: Context<ReleaseMilestone>,
+    _organisation_id: String,
+    _contract_id: String,
+    index: u8,
+) -> Result<()> {
+    let milestone_contract = &ctx.accounts.milestone_contract;
+    let milestone = milestone_contract
+        .milestones
+        .get(index as usize)
+        .ok_or(NovaError::InvalidMilestones)?;
+    if milestone.status != MilestoneStatus::Submitted {
+        return err!(NovaError::InvalidMilestoneStatus)
+    }
+    let now = Clock::get()?.unix_timestamp;
+    if now < milestone.submitted_at.saturating_add(milestone_contract.dispute_window) {
+        return err!(NovaError::DisputeWindowOpen)
+    }
+    if now < milestone.due_date {
+        return err!(NovaError::MilestoneNotDue)
+    }
+
+    release_milestone(ctx, _organisation_id, _contract_id, index)
+}
+
+pub fn cancel_milestone_contract(
+    ctx: Context<CancelMilestoneContract>,
+    _organisation_id: String,
+    _contract_id: String,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.milestone_contract.status != MilestoneContractStatus::Active {
+        return err!(NovaError::ContractNotActive)
+    }
+
+    let refund = ctx.accounts.milestone_contract.unreleased_amount();
+    let milestone_contract = &mut ctx.accounts.milestone_contract;
+    milestone_contract.status = MilestoneContractStatus::Cancelled;
+    for milestone in milestone_contract.milestones.iter_mut() {
+        if milestone.status != MilestoneStatus::Released {
+            milestone.status = MilestoneStatus::Cancelled;
+        }
+    }
+    milestone_contract.released_amount = milestone_contract.total_amount;
+
+    if refund > 0 {
+        let bump = ctx.accounts.milestone_contract.bump;
+        let signer_seeds = &[
+            b"milestone-contract".as_ref(),
+            _organisation_id.as_bytes(),
+            _contract_id.as_bytes(),
+            &[bump],
+        ];
+        let signer = &[&signer_seeds[..]];
+
+        transfer_checked(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.escrow.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.streaming_wallet_token_account.to_account_info(),
+            ctx.accounts.milestone_contract.to_account_info(),
+            signer,
+            refund,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn release_milestone(
+    ctx: Context<ReleaseMilestone>,
+    _organisation_id: String,
+    _contract_id: String,
+    index: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let milestone_contract = &mut ctx.accounts.milestone_contract;
+    let milestone = &mut milestone_contract.milestones[index as usize];
+    milestone.status = MilestoneStatus::Released;
+    let amount = milestone.amount;
+    milestone_contract.released_amount += amount;
+    if milestone_contract.is_settled() {
+        milestone_contract.status = MilestoneContractStatus::Completed;
+    }
+
+    let bump = milestone_contract.bump;
+    let signer_seeds = &[
+        b"milestone-contract".as_ref(),
+        _organisation_id.as_bytes(),
+        _contract_id.as_bytes(),
+        &[bump],
+    ];
+    let signer = &[&signer_seeds[..]];
+
+    transfer_checked(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.escrow.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.contractor_token_account.to_account_info(),
+        ctx.accounts.milestone_contract.to_account_info(),
+        signer,
+        amount,
+    )?;
+
+    emit!(MilestoneUpdated {
+        milestone_contract: ctx.accounts.milestone_contract.key(),
+        index,
+        status: MilestoneStatus::Released,
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _contract_id: String)]
+pub struct SetupMilestoneContract<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + MilestoneContract::SIZE,
+        seeds = [b"milestone-contract", _organisation_id.as_bytes(), _contract_id.as_bytes()],
+        bump
+    )]
+    pub milestone_contract: Box<Account<'info, MilestoneContract>>,
+    #[account(
+        mut,
+        seeds = [b"milestone-escrow", milestone_contract.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is not dangerous because it is created as a token account of `mint` by the instruction
+    pub escrow: AccountInfo<'info>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        seeds = [b"streaming-wallet", _organisation_id.as_bytes()],
+        bump = organisation.stream_wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub streaming_wallet: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_account(&streaming_wallet_token_account)?.mint == mint.key() @ NovaError::NotStreamingWalletTokenAccount,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub streaming_wallet_token_account: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub mint: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub contractor: AccountInfo<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(constraint = is_token_program(token_program.key) @ NovaError::InvalidTokenProgram)]
+    /// CHECK: This is not dangerous because it must be one of the token programs
+    pub token_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _contract_id: String)]
+pub struct SubmitMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [b"milestone-contract", _organisation_id.as_bytes(), _contract_id.as_bytes()],
+        bump = milestone_contract.bump,
+        has_one = contractor,
+    )]
+    pub milestone_contract: Box<Account<'info, MilestoneContract>>,
+    pub contractor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _contract_id: String)]
+pub struct ManageMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [b"milestone-contract", _organisation_id.as_bytes(), _contract_id.as_bytes()],
+        bump = milestone_contract.bump,
+    )]
+    pub milestone_contract: Box<Account<'info, MilestoneContract>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _contract_id: String)]
+pub struct ReleaseMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [b"milestone-contract", _organisation_id.as_bytes(), _contract_id.as_bytes()],
+        bump = milestone_contract.bump,
+        has_one = mint,
+    )]
+    pub milestone_contract: Box<Account<'info, MilestoneContract>>,
+    #[account(
+        mut,
+        seeds = [b"milestone-escrow", milestone_contract.key().as_ref()],
+        bump = milestone_contract.escrow_bump,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub escrow: AccountInfo<'info>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        mut,
+        constraint = token_account(&contractor_token_account)?.owner == milestone_contract.contractor @ NovaError::NotEmployeeTokenAccount,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub contractor_token_account: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it must be the milestone contract's mint
+    pub mint: AccountInfo<'info>,
+    // Admin approving the milestone, or any keeper once the dispute window has passed
+    pub authority: Signer<'info>,
+    #[account(constraint = is_token_program(token_program.key) @ NovaError::InvalidTokenProgram)]
+    /// CHECK: This is not dangerous because it must be one of the token programs
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _contract_id: String)]
+pub struct CancelMilestoneContract<'info> {
+    #[account(
+        mut,
+        seeds = [b"milestone-contract", _organisation_id.as_bytes(), _contract_id.as_bytes()],
+        bump = milestone_contract.bump,
+        has_one = mint,
+    )]
+    pub milestone_contract: Box<Account<'info, MilestoneContract>>,
+    #[account(
+        mut,
+        seeds = [b"milestone-escrow", milestone_contract.key().as_ref()],
+        bump = milestone_contract.escrow_bump,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub escrow: AccountInfo<'info>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        seeds = [b"streaming-wallet", _organisation_id.as_bytes()],
+        bump = organisation.stream_wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub streaming_wallet: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_account(&streaming_wallet_token_account)?.owner == streaming_wallet.key() @ NovaError::NotStreamingWalletTokenAccount,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub streaming_wallet_token_account: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it must be the milestone contract's mint
+    pub mint: AccountInfo<'info>,
+    pub admin: Signer<'info>,
+    #[account(constraint = is_token_program(token_program.key) @ NovaError::InvalidTokenProgram)]
+    /// CHECK: This is not dangerous because it must be one of the token programs
+    pub token_program: AccountInfo<'info>,
+}
\ No newline at end of file
@@ -1,7 +1,41 @@
 pub use employee::*;
 pub use organisation::*;
 pub use stream::*;
+pub use protocol::*;
+pub use advance::*;
+pub use loan::*;
+pub use deduction::*;
+pub use contribution::*;
+pub use expense::*;
+pub use milestone::*;
+pub use timesheet::*;
+pub use dispute::*;
+pub use leave::*;
+pub use payout::*;
+pub use standing_order::*;
+pub use lock::*;
+pub use holding_authority::*;
+pub use rebalance::*;
+pub use emergency::*;
+pub use migration::*;
 
 pub mod employee;
 pub mod organisation;
-pub mod stream;
\ No newline at end of file
+pub mod stream;
+pub mod protocol;
+pub mod advance;
+pub mod loan;
+pub mod deduction;
+pub mod contribution;
+pub mod expense;
+pub mod milestone;
+pub mod timesheet;
+pub mod dispute;
+pub mod leave;
+pub mod payout;
+pub mod standing_order;
+pub mod lock;
+pub mod holding_authority;
+pub mod rebalance;
+pub mod emergency;
+pub mod migration;
\ No newline at end of file
@@ -1,59 +1,86 @@
-use crate::state::{organisation::Organisation, HoldingWalletState, EmployeeContract};
+use crate::error::NovaError;
+use crate::events::{ContractTerminated, OrganisationAdminUpdated};
+use crate::instructions::loan::write_off_loan;
+use crate::instructions::stream::*;
+use crate::state::{organisation::Organisation, MAX_ADMINS, HoldingWalletState, EmployeeContract, ContractStatus, LoanAccount, LoanStatus, PayMode, PaymentKind, PaymentPolicy, TerminationReason, Currency, RateOpening};
+use crate::oracle::{DEFAULT_MAX_PRICE_AGE, DEFAULT_MAX_PRICE_CONFIDENCE_BPS};
+use crate::token_interface::token_account;
+use crate::utils::BPS_DENOMINATOR;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token::{Mint, TokenAccount};
 use mercurial_vault::{cpi::accounts::DepositWithdrawLiquidity, program::Vault};
 
-pub fn pay_organisation_employee(
-    ctx: Context<PayOrganisationEmployee>,
+// Pays `amount` like a salary `pay_contract`, with the holding wallet token account as the
+// employee token account. The holding wallet's Meteora allocation of the net pay is kept in the
+// holding wallet ahead of the payee's payout instructions and moved into the vault, the rest
+// follows the payout instructions. The deposit is signed by the holding wallet and mints its LP
+// straight into the holding wallet's LP account, so the yield on it accrues to the employee
+// alone. Remaining accounts are those of `pay_contract`.
+pub fn pay_organisation_employee<'info>(
+    ctx: Context<'_, '_, '_, 'info, PayOrganisationEmployee<'info>>,
     _organisation_id: String,
     amount: u64,
+    rate_opening: Option<RateOpening>,
 ) -> Result<()> {
-    let balance = ctx.accounts.payer_token_account.amount;
-    if balance < amount {
-        panic!("Not enough balance")
-    }
-
-    let meteora_allocation_percentage =
-        ctx.accounts.holding_wallet_state.clone().meteora_allocation as u64;
-    let meteora_allocation = amount * meteora_allocation_percentage / 100;
-    let holding_allocation = amount - meteora_allocation;
-
-    // print!("Paying organisation employee {}", holding_allocation);
-    let mercurial_accounts = DepositWithdrawLiquidity {
-        vault: ctx.accounts.vault.to_account_info(),
-        token_vault: ctx.accounts.token_vault.to_account_info(),
-        lp_mint: ctx.accounts.lp_mint.to_account_info(),
-        user_token: ctx.accounts.payer_token_account.to_account_info(),
-        user_lp: ctx
-            .accounts
-            .holding_wallet_lp_token_account
-            .to_account_info(),
-        user: ctx.accounts.payer.to_account_info(),
-        token_program: ctx.accounts.token_program.to_account_info(),
-    };
-    print!("After mercurial");
-
-    let mer_context = CpiContext::new(
-        ctx.accounts.mercurial_program.to_account_info(),
-        mercurial_accounts,
-    );
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.payer_token_account.to_account_info(),
-        to: ctx.accounts.holding_wallet_token_account.to_account_info(),
-        authority: ctx.accounts.payer.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-
-    print!("BEFORE mercurial");
-    mercurial_vault::cpi::deposit(mer_context, meteora_allocation, 0)?;
-    print!("AFTER mercurial");
+    if ctx.accounts.pay.employee_contract.pay_mode == PayMode::Hourly {
+        return err!(NovaError::HourlyContractRequiresTimesheet)
+    }
 
-    print!("BEFORE token transfer");
-    token::transfer(
-        CpiContext::new(cpi_program, cpi_accounts),
-        holding_allocation,
+    let idle_balance = token_account(&ctx.accounts.pay.employee_token_account)?.amount;
+    let meteora_allocation_percentage = ctx.accounts.holding_wallet_state.meteora_allocation;
+    let receipt_bump = *ctx.bumps.get("receipt").unwrap();
+    settle_payment(
+        &mut ctx.accounts.pay,
+        ctx.remaining_accounts,
+        receipt_bump,
+        &_organisation_id,
+        amount,
+        PaymentKind::Salary,
+        rate_opening.as_ref(),
+        false,
+        meteora_allocation_percentage,
     )?;
-    print!("AFTER token transfer");
+    let received = token_account(&ctx.accounts.pay.employee_token_account)?.amount.saturating_sub(idle_balance);
+
+    let meteora_allocation = (ctx.accounts.pay.receipt.net_amount * meteora_allocation_percentage as u64 / 100).min(received);
+    if meteora_allocation > 0 {
+        let payee = ctx.accounts.pay.employee_contract.payee;
+        let signer_seeds = &[
+            b"holding-wallet".as_ref(),
+            payee.as_ref(),
+            _organisation_id.as_bytes(),
+            &[ctx.accounts.holding_wallet_state.wallet_bump],
+        ];
+        let signer: &[&[&[u8]]; 1] = &[&signer_seeds[..]];
+        let mercurial_accounts = DepositWithdrawLiquidity {
+            vault: ctx.accounts.vault.to_account_info(),
+            token_vault: ctx.accounts.token_vault.to_account_info(),
+            lp_mint: ctx.accounts.lp_mint.to_account_info(),
+            user_token: ctx.accounts.pay.employee_token_account.to_account_info(),
+            user_lp: ctx.accounts.holding_wallet_lp_token_account.to_account_info(),
+            user: ctx.accounts.holding_wallet.to_account_info(),
+            token_program: ctx.accounts.pay.token_program.to_account_info(),
+        };
+        let mer_context = CpiContext::new(
+            ctx.accounts.mercurial_program.to_account_info(),
+            mercurial_accounts,
+        ).with_signer(signer);
+        mercurial_vault::cpi::deposit(mer_context, meteora_allocation, 0)?;
+
+        let holding_wallet_lp_token_account = &mut ctx.accounts.holding_wallet_lp_token_account;
+        holding_wallet_lp_token_account.reload()?;
+        if holding_wallet_lp_token_account.owner != ctx.accounts.holding_wallet.key()
+            || holding_wallet_lp_token_account.mint != ctx.accounts.lp_mint.key()
+        {
+            return err!(NovaError::NotHoldingWalletTokenAccount)
+        }
+
+        let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+        holding_wallet_state.principal = holding_wallet_state
+            .principal
+            .checked_add(meteora_allocation)
+            .ok_or(NovaError::MathOverflow)?;
+    }
 
     Ok(())
 }
@@ -67,28 +94,337 @@ pub fn setup_organisation(
     let streaming_wallet_bump = *ctx.bumps.get("streaming_wallet").unwrap();
     let treasury_delegate_bump = *ctx.bumps.get("treasury_delegate").unwrap();
 
-    let mut admins:  Vec<Pubkey> = Vec::new();
-    admins.push(ctx.accounts.admin.key());
-    organisation.admins = admins;
+    organisation.admins = vec![ctx.accounts.admin.key()];
     organisation.bump = bump;
     organisation.stream_wallet_bump = streaming_wallet_bump;
     organisation.stream_authority = ctx.accounts.stream_authority.key();
     organisation.treasury_delegate_bump = treasury_delegate_bump;
+    organisation.advance_limit_bps = 0;
+    organisation.advance_fee = 0;
+    organisation.payment_policies = Default::default();
+    organisation.dispute_window = 0;
+    organisation.arbiter = Pubkey::default();
+    organisation.dispute_approvals = 0;
+    organisation.max_price_age = DEFAULT_MAX_PRICE_AGE;
+    organisation.max_price_confidence_bps = DEFAULT_MAX_PRICE_CONFIDENCE_BPS;
+    organisation.early_unlock_allowed = false;
+    organisation.early_unlock_penalty_bps = 0;
+    organisation.version = Organisation::VERSION;
+    organisation.reserved = [0; 64];
 
     Ok(())
 }
 
+// A non-zero `rate_commitment` sets the contract up with a committed rate, see
+// `set_rate_commitment`, in which case `rate` must be zero
 pub fn setup_employee_contract(
     ctx: Context<SetupEmployeeContract>,
     _organisation_id: String,
     _employee_id: String,
     rate: u64,
+    pay_period: i64,
+    pay_mode: PayMode,
+    rate_commitment: [u8; 32],
 ) -> Result<()> {
+    if pay_period <= 0 {
+        return err!(NovaError::InvalidPayPeriod)
+    }
+    if rate_commitment != [0; 32] && rate != 0 {
+        return err!(NovaError::InvalidRateOpening)
+    }
+
     let bump = *ctx.bumps.get("employee_contract").unwrap();
     let employee_contract = &mut ctx.accounts.employee_contract;
     employee_contract.payee = ctx.accounts.payee.key();
     employee_contract.bump = bump;
     employee_contract.rate = rate;
+    employee_contract.pay_period = pay_period;
+    employee_contract.last_paid_at = Clock::get()?.unix_timestamp;
+    employee_contract.outstanding_advance = 0;
+    employee_contract.status = ContractStatus::Active;
+    employee_contract.loan = Pubkey::default();
+    employee_contract.deductions = Pubkey::default();
+    employee_contract.contributions = Pubkey::default();
+    employee_contract.employer_contribution_bps = 0;
+    employee_contract.total_employer_contributions = 0;
+    employee_contract.payment_count = 0;
+    employee_contract.pay_mode = pay_mode;
+    employee_contract.termination_reason = TerminationReason::None;
+    employee_contract.terminated_at = 0;
+    employee_contract.leave_accrual_rate = 0;
+    employee_contract.leave_balance = 0;
+    employee_contract.leave_accrued_at = employee_contract.last_paid_at;
+    employee_contract.unpaid_leave = 0;
+    employee_contract.currency = Currency::USD;
+    employee_contract.price_feed = Pubkey::default();
+    employee_contract.rate_commitment = rate_commitment;
+    employee_contract.last_timesheet_end = 0;
+    employee_contract.holding_wallet_bump = EmployeeContract::find_holding_wallet_bump(&employee_contract.payee, &_organisation_id);
+    employee_contract.last_leave_end = 0;
+    employee_contract.version = EmployeeContract::VERSION;
+    employee_contract.reserved = [0; 64];
+
+    Ok(())
+}
+pub fn configure_payment_policy(
+    ctx: Context<ConfigurePaymentPolicy>,
+    _organisation_id: String,
+    payment_kind: PaymentKind,
+    approval_threshold: u64,
+    required_approvals: u8,
+) -> Result<()> {
+    let organisation = &mut ctx.accounts.organisation;
+    if !organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if required_approvals as usize > organisation.admins.len() {
+        return err!(NovaError::MissingApprovals)
+    }
+
+    organisation.payment_policies[payment_kind as usize] = PaymentPolicy {
+        approval_threshold,
+        required_approvals,
+    };
+
+    Ok(())
+}
+
+pub fn configure_price_limits(
+    ctx: Context<ConfigurePriceLimits>,
+    _organisation_id: String,
+    max_price_age: i64,
+    max_price_confidence_bps: u16,
+) -> Result<()> {
+    let organisation = &mut ctx.accounts.organisation;
+    if !organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if max_price_age <= 0 || max_price_confidence_bps as u64 > BPS_DENOMINATOR {
+        return err!(NovaError::InvalidPriceFeed)
+    }
+
+    organisation.max_price_age = max_price_age;
+    organisation.max_price_confidence_bps = max_price_confidence_bps;
+
+    Ok(())
+}
+
+pub fn add_admin(
+    ctx: Context<AddAdmin>,
+    _organisation_id: String,
+    new_admin: Pubkey,
+) -> Result<()> {
+    let organisation = &mut ctx.accounts.organisation;
+    if !organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if organisation.is_admin(&new_admin) || organisation.admins.len() >= MAX_ADMINS {
+        return err!(NovaError::InvalidAdmin)
+    }
+
+    organisation.admins.push(new_admin);
+
+    emit!(OrganisationAdminUpdated {
+        organisation: organisation.key(),
+        admin: new_admin,
+        removed: false,
+        admin_count: organisation.admins.len() as u32,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Admins can't drop below what the approval policies require, the freed rent goes to the signer
+pub fn remove_admin(
+    ctx: Context<RemoveAdmin>,
+    _organisation_id: String,
+    removed_admin: Pubkey,
+) -> Result<()> {
+    let organisation = &mut ctx.accounts.organisation;
+    if !organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    let index = organisation
+        .admins
+        .iter()
+        .position(|admin| *admin == removed_admin)
+        .ok_or(NovaError::InvalidAdmin)?;
+    if organisation.admins.len() - 1 < organisation.min_admins() {
+        return err!(NovaError::InvalidAdmin)
+    }
+
+    organisation.admins.remove(index);
+
+    emit!(OrganisationAdminUpdated {
+        organisation: organisation.key(),
+        admin: removed_admin,
+        removed: true,
+        admin_count: organisation.admins.len() as u32,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn configure_early_unlock(
+    ctx: Context<ConfigureEarlyUnlock>,
+    _organisation_id: String,
+    early_unlock_allowed: bool,
+    early_unlock_penalty_bps: u16,
+) -> Result<()> {
+    let organisation = &mut ctx.accounts.organisation;
+    if !organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if early_unlock_penalty_bps as u64 > BPS_DENOMINATOR {
+        return err!(NovaError::InvalidFeeBps)
+    }
+
+    organisation.early_unlock_allowed = early_unlock_allowed;
+    organisation.early_unlock_penalty_bps = early_unlock_penalty_bps;
+
+    Ok(())
+}
+
+// Non-USD contracts are converted into the payout mint at the feed's price on each payment
+pub fn set_contract_currency(
+    ctx: Context<SetContractCurrency>,
+    _organisation_id: String,
+    _employee_id: String,
+    currency: Currency,
+    price_feed: Pubkey,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if (currency == Currency::USD) != (price_feed == Pubkey::default()) {
+        return err!(NovaError::InvalidPriceFeed)
+    }
+
+    let employee_contract = &mut ctx.accounts.employee_contract;
+    employee_contract.currency = currency;
+    employee_contract.price_feed = price_feed;
+
+    Ok(())
+}
+
+// Replaces the public rate with a salted hash commitment to it, see `RateOpening`. Instructions
+// that price from the rate then need the opening, a zero commitment makes the rate public again
+// at `rate`.
+pub fn set_rate_commitment(
+    ctx: Context<SetRateCommitment>,
+    _organisation_id: String,
+    _employee_id: String,
+    rate: u64,
+    rate_commitment: [u8; 32],
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if rate_commitment != [0; 32] && rate != 0 {
+        return err!(NovaError::InvalidRateOpening)
+    }
+
+    let employee_contract = &mut ctx.accounts.employee_contract;
+    employee_contract.rate = rate;
+    employee_contract.rate_commitment = rate_commitment;
+
+    Ok(())
+}
+
+// Settles the final pay in a single severance payment: salary accrued up to now for salaried
+// contracts net of unpaid leave, `severance_periods` periods of `rate` and, when `pay_out_leave`
+// is set, the unused leave balance. Remaining accounts are those of `pay_contract`, any loan left
+// active after the final installment has its outstanding balance written off since the contract
+// no longer produces payments.
+pub fn terminate_contract<'info>(
+    ctx: Context<'_, '_, '_, 'info, TerminateContract<'info>>,
+    _organisation_id: String,
+    _employee_id: String,
+    reason: TerminationReason,
+    severance_periods: u16,
+    pay_out_leave: bool,
+    rate_opening: Option<RateOpening>,
+) -> Result<()> {
+    if !ctx.accounts.pay.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if reason == TerminationReason::None {
+        return err!(NovaError::InvalidTerminationReason)
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let employee_contract = &mut ctx.accounts.pay.employee_contract;
+    let rate = employee_contract.open_rate(rate_opening.as_ref())?;
+    let pro_rata_amount = if employee_contract.pay_mode == PayMode::Salaried {
+        let accrued = employee_contract.accrued_since_last_payment(rate, now);
+        accrued - employee_contract.withhold_unpaid_leave(rate, accrued)
+    } else {
+        0
+    };
+    // The hourly rate is not a per-period rate, so hourly work is settled through timesheets and
+    // neither severance nor leave can be valued from it
+    if employee_contract.pay_mode == PayMode::Hourly && (severance_periods > 0 || pay_out_leave) {
+        return err!(NovaError::ContractNotSalaried)
+    }
+    let severance_amount = rate
+        .checked_mul(severance_periods as u64)
+        .ok_or(NovaError::MathOverflow)?;
+    employee_contract.accrue_leave(now);
+    let leave_payout = if pay_out_leave {
+        employee_contract.leave_payout(rate, employee_contract.leave_balance)
+    } else {
+        0
+    };
+    employee_contract.leave_balance = 0;
+    employee_contract.unpaid_leave = 0;
+    let amount = pro_rata_amount
+        .checked_add(severance_amount)
+        .and_then(|amount| amount.checked_add(leave_payout))
+        .ok_or(NovaError::MathOverflow)?;
+
+    let receipt_bump = *ctx.bumps.get("receipt").unwrap();
+    let payment_index = settle_payment(
+        &mut ctx.accounts.pay,
+        ctx.remaining_accounts,
+        receipt_bump,
+        &_organisation_id,
+        amount,
+        PaymentKind::Severance,
+        rate_opening.as_ref(),
+        false,
+        0,
+    )?;
+
+    // Final pay has already recovered as much of the loan as it could, the rest is written off
+    let employee_contract = &mut ctx.accounts.pay.employee_contract;
+    if employee_contract.has_loan() {
+        let loan_info = ctx.remaining_accounts
+            .iter()
+            .find(|info| info.key() == employee_contract.loan)
+            .ok_or(NovaError::MissingLoanAccounts)?;
+        let mut loan: Account<LoanAccount> = Account::try_from(loan_info)?;
+        if loan.status == LoanStatus::Active {
+            write_off_loan(&mut loan, employee_contract);
+            loan.exit(&crate::ID)?;
+        }
+    }
+
+    employee_contract.last_paid_at = now;
+    employee_contract.status = ContractStatus::Terminated;
+    employee_contract.termination_reason = reason;
+    employee_contract.terminated_at = now;
+
+    emit!(ContractTerminated {
+        employee_contract: employee_contract.key(),
+        reason,
+        payment_index,
+        pro_rata_amount,
+        severance_amount,
+        leave_payout,
+        timestamp: now,
+    });
 
     Ok(())
 }
@@ -99,8 +435,8 @@ pub struct SetupOrganisation<'info> {
     #[account(
         init, 
         payer = admin, 
-        space = Organisation::SIZE + 8, 
-        seeds = [b"organisation", _organisation_id.as_bytes().as_ref()], 
+        space = Organisation::space(1),
+        seeds = [b"organisation", _organisation_id.as_bytes()], 
         bump
     )]
     pub organisation: Account<'info, Organisation>,
@@ -130,30 +466,28 @@ pub struct SetupOrganisation<'info> {
 #[instruction(_organisation_id: String, _employee_id: String, _amount: u64)]
 pub struct PayOrganisationEmployee<'info> {
     #[account(
-        seeds = [b"holding-wallet", employee.key().as_ref()],
-        bump=holding_wallet_state.wallet_bump,
+        constraint = token_account(&pay.employee_token_account)?.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = pay.mint.key() == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub pay: PayContract<'info>,
+    #[account(
+        seeds = [b"holding-wallet", pay.employee_contract.payee.as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.wallet_bump,
     )]
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub holding_wallet: AccountInfo<'info>,
     #[account(
-        seeds = [b"holding-state", employee.key().as_ref(), _organisation_id.as_bytes().as_ref()],
+        mut,
+        seeds = [b"holding-state", pay.employee_contract.payee.as_ref(), _organisation_id.as_bytes()],
         bump = holding_wallet_state.bump,
     )]
     pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
-    #[account(mut)]
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    pub employee: AccountInfo<'info>,
-    // Token Account stuff
-    pub token_mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub holding_wallet_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut)]
-    pub payer_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub payer_lp_token_account: Account<'info, TokenAccount>,
-    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(
+        mut,
+        constraint = holding_wallet_lp_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_lp_token_account.mint == lp_mint.key() @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_lp_token_account: Box<Account<'info, TokenAccount>>,
     #[account(
         mut,
         has_one = token_vault,
@@ -161,16 +495,12 @@ pub struct PayOrganisationEmployee<'info> {
     )]
     pub vault: Box<Account<'info, mercurial_vault::state::Vault>>,
     #[account(mut)]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub token_vault: Box<Account<'info, TokenAccount>>,
     #[account(mut)]
-    pub lp_mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub holding_wallet_lp_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub lp_mint: Box<Account<'info, Mint>>,
     pub mercurial_program: Program<'info, Vault>,
 }
 
-
 #[derive(Accounts)]
 #[instruction(_organisation_id: String, _employee_id: String)]
 pub struct SetupEmployeeContract<'info> {
@@ -179,7 +509,7 @@ pub struct SetupEmployeeContract<'info> {
         payer = payer,
         seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
         bump,
-        space = EmployeeContract::SIZE,
+        space = 8 + EmployeeContract::SIZE,
     )]
     pub employee_contract: Box<Account<'info, EmployeeContract>>,
     #[account(
@@ -193,4 +523,115 @@ pub struct SetupEmployeeContract<'info> {
     pub payer: Signer<'info>,
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct ConfigurePriceLimits<'info> {
+    #[account(
+        mut,
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Account<'info, Organisation>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct ConfigureEarlyUnlock<'info> {
+    #[account(
+        mut,
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Account<'info, Organisation>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct AddAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+        realloc = Organisation::space(organisation.admins.len() + 1),
+        realloc::payer = admin,
+        realloc::zero = false,
+    )]
+    pub organisation: Account<'info, Organisation>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct RemoveAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+        realloc = Organisation::space(organisation.admins.len().saturating_sub(1)),
+        realloc::payer = admin,
+        realloc::zero = false,
+    )]
+    pub organisation: Account<'info, Organisation>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct SetContractCurrency<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct SetRateCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String)]
+pub struct TerminateContract<'info> {
+    pub pay: PayContract<'info>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct ConfigurePaymentPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Account<'info, Organisation>,
+    pub admin: Signer<'info>,
 }
\ No newline at end of file
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::state::{PayoutDestination, PayoutInstructions, MAX_PAYOUT_DESTINATIONS};
+use crate::token_interface::{token_account, transfer_checked, transfer_fee, TOKEN_2022_PROGRAM_ID};
+use crate::utils::{bps_of, BPS_DENOMINATOR};
+
+pub fn set_payout_instructions(
+    ctx: Context<SetPayoutInstructions>,
+    destinations: Vec<PayoutDestination>,
+) -> Result<()> {
+    if destinations.len() > MAX_PAYOUT_DESTINATIONS {
+        return err!(NovaError::InvalidPayoutInstructions)
+    }
+    let total_bps: u64 = destinations.iter().map(|destination| destination.bps as u64).sum();
+    if !destinations.is_empty() && total_bps != BPS_DENOMINATOR {
+        return err!(NovaError::InvalidPayoutInstructions)
+    }
+
+    let payout_instructions = &mut ctx.accounts.payout_instructions;
+    payout_instructions.owner = ctx.accounts.owner.key();
+    payout_instructions.bump = *ctx.bumps.get("payout_instructions").unwrap();
+    payout_instructions.destinations = destinations;
+
+    Ok(())
+}
+
+// Pays `amount` out to `owner` following their payout instructions. Expects the owner's
+// payout instructions address as the next remaining account, whether or not it has been set
+// up, so the split can't be skipped, followed by one token account per destination when it has.
+// Without instructions everything goes to `default_destination`, otherwise the last destination
+// takes the rounding remainder. Returns the transfer fees the mint withheld from the payout.
+// Token-2022 mints can't be paid into the owner's holding wallet for `organisation_id`, whose
+// flows are SPL Token only.
+#[allow(clippy::too_many_arguments)]
+pub fn distribute_payout<'info>(
+    remaining_accounts: &mut std::slice::Iter<AccountInfo<'info>>,
+    owner: &Pubkey,
+    organisation_id: &str,
+    token_program: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    default_destination: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    amount: u64,
+) -> Result<u64> {
+    let instructions_info = remaining_accounts.next().ok_or(NovaError::MissingPayoutAccounts)?;
+    if instructions_info.key() != PayoutInstructions::address(owner) {
+        return err!(NovaError::InvalidPayoutInstructions)
+    }
+
+    let mut destinations = Vec::new();
+    if *instructions_info.owner == crate::ID && !instructions_info.data_is_empty() {
+        let payout_instructions: Account<PayoutInstructions> = Account::try_from(instructions_info)?;
+        destinations = payout_instructions.destinations.clone();
+    }
+
+    let holding_wallet = if *mint.owner == TOKEN_2022_PROGRAM_ID {
+        Some(Pubkey::find_program_address(&[b"holding-wallet", owner.as_ref(), organisation_id.as_bytes()], &crate::ID).0)
+    } else {
+        None
+    };
+    let transfer = |to: AccountInfo<'info>, amount: u64| -> Result<u64> {
+        if let Some(holding_wallet) = holding_wallet {
+            if token_account(&to)?.owner == holding_wallet {
+                return err!(NovaError::UnsupportedHoldingWalletMint)
+            }
+        }
+        transfer_checked(token_program.clone(), from.clone(), mint.clone(), to, authority.clone(), signer, amount)?;
+        transfer_fee(&mint, amount)
+    };
+
+    if destinations.is_empty() {
+        return transfer(default_destination, amount)
+    }
+
+    let mut fees = 0;
+    let mut remaining_amount = amount;
+    for (index, destination) in destinations.iter().enumerate() {
+        let destination_info = remaining_accounts.next().ok_or(NovaError::MissingPayoutAccounts)?;
+        if destination_info.key() != destination.destination {
+            return err!(NovaError::InvalidPayoutInstructions)
+        }
+
+        let share = if index == destinations.len() - 1 {
+            remaining_amount
+        } else {
+            bps_of(amount, destination.bps)
+        };
+        remaining_amount -= share;
+        if share > 0 {
+            fees += transfer(destination_info.clone(), share)?;
+        }
+    }
+
+    Ok(fees)
+}
+
+#[derive(Accounts)]
+pub struct SetPayoutInstructions<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + PayoutInstructions::SIZE,
+        seeds = [b"payout-instructions", owner.key().as_ref()],
+        bump
+    )]
+    pub payout_instructions: Box<Account<'info, PayoutInstructions>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
\ No newline at end of file
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::program::PulseEor;
+use crate::state::{ProtocolConfig, DEFAULT_REBALANCE_DRIFT_BPS};
+use crate::utils::BPS_DENOMINATOR;
+
+// Only the program's upgrade authority can create the config, which makes it the config authority
+pub fn setup_protocol_config(
+    ctx: Context<SetupProtocolConfig>,
+    fee_recipient: Pubkey,
+    performance_fee_bps: u16,
+) -> Result<()> {
+    if performance_fee_bps as u64 > BPS_DENOMINATOR {
+        return err!(NovaError::InvalidFeeBps)
+    }
+
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    protocol_config.authority = ctx.accounts.authority.key();
+    protocol_config.fee_recipient = fee_recipient;
+    protocol_config.performance_fee_bps = performance_fee_bps;
+    protocol_config.bump = *ctx.bumps.get("protocol_config").unwrap();
+    protocol_config.rebalance_drift_bps = DEFAULT_REBALANCE_DRIFT_BPS;
+    protocol_config.rebalance_bounty_bps = 0;
+    protocol_config.version = ProtocolConfig::VERSION;
+    protocol_config.reserved = [0; 64];
+
+    Ok(())
+}
+
+pub fn update_protocol_config(
+    ctx: Context<UpdateProtocolConfig>,
+    fee_recipient: Pubkey,
+    performance_fee_bps: u16,
+) -> Result<()> {
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    if performance_fee_bps as u64 + protocol_config.rebalance_bounty_bps as u64 > BPS_DENOMINATOR {
+        return err!(NovaError::InvalidFeeBps)
+    }
+
+    protocol_config.fee_recipient = fee_recipient;
+    protocol_config.performance_fee_bps = performance_fee_bps;
+
+    Ok(())
+}
+
+// The keeper bounty and the performance fee are both taken from the realised yield
+pub fn configure_rebalancing(
+    ctx: Context<UpdateProtocolConfig>,
+    rebalance_drift_bps: u16,
+    rebalance_bounty_bps: u16,
+) -> Result<()> {
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    if rebalance_drift_bps as u64 > BPS_DENOMINATOR
+        || protocol_config.performance_fee_bps as u64 + rebalance_bounty_bps as u64 > BPS_DENOMINATOR
+    {
+        return err!(NovaError::InvalidFeeBps)
+    }
+
+    protocol_config.rebalance_drift_bps = rebalance_drift_bps;
+    protocol_config.rebalance_bounty_bps = rebalance_bounty_bps;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetupProtocolConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProtocolConfig::SIZE,
+        seeds = [b"protocol-config"],
+        bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ NovaError::NotUpgradeAuthority)]
+    pub program: Program<'info, PulseEor>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ NovaError::NotUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = authority,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub authority: Signer<'info>,
+}
\ No newline at end of file
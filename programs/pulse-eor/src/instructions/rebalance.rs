@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use mercurial_vault::{cpi::accounts::DepositWithdrawLiquidity, program::Vault};
+
+use crate::error::NovaError;
+use crate::events::{HoldingWalletRebalanced, YieldRealised};
+use crate::state::{HoldingWalletState, ProtocolConfig};
+use crate::utils::{lp_to_underlying, underlying_to_lp, vault_can_pay_out, BPS_DENOMINATOR};
+
+// Permissionless, moves the holding wallet back to its configured split once the vault's share
+// of the balance has drifted by more than the protocol's `rebalance_drift_bps`. The wallet belongs
+// to the state's organisation alone, so the drift is measured on the wallet's whole balance and
+// exits realise yield against the cost basis of all of its LP. The keeper's bounty is taken from
+// that yield alongside the performance fee and capped at what the fee leaves of it, so deposits
+// into the vault carry no bounty and the principal is never charged.
+pub fn rebalance_holding_wallet(
+    ctx: Context<RebalanceHoldingWallet>,
+    _organisation_id: String,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let idle_balance = ctx.accounts.holding_wallet_token_account.amount;
+    let lp_balance = ctx.accounts.holding_wallet_lp_token_account.amount;
+    let lp_value = lp_to_underlying(&ctx.accounts.vault, lp_balance, ctx.accounts.lp_mint.supply)?;
+    let total = idle_balance.checked_add(lp_value).ok_or(NovaError::MathOverflow)?;
+    if total == 0 {
+        return err!(NovaError::RebalanceNotNeeded)
+    }
+
+    let meteora_allocation = ctx.accounts.holding_wallet_state.meteora_allocation as u128;
+    let target_value = (total as u128 * meteora_allocation / 100) as u64;
+    let current_bps = lp_value as u128 * BPS_DENOMINATOR as u128 / total as u128;
+    let drift_bps = current_bps.abs_diff(meteora_allocation * 100) as u64;
+    if drift_bps <= ctx.accounts.protocol_config.rebalance_drift_bps as u64 {
+        return err!(NovaError::RebalanceNotNeeded)
+    }
+
+    let payee = ctx.accounts.payee.key();
+    let signer_seeds = &[
+        b"holding-wallet".as_ref(),
+        payee.as_ref(),
+        _organisation_id.as_bytes(),
+        &[ctx.accounts.holding_wallet_state.wallet_bump],
+    ];
+    let signer: &[&[&[u8]]; 1] = &[&signer_seeds[..]];
+
+    let mecurial_accounts = DepositWithdrawLiquidity {
+        vault: ctx.accounts.vault.to_account_info(),
+        token_vault: ctx.accounts.token_vault.to_account_info(),
+        lp_mint: ctx.accounts.lp_mint.to_account_info(),
+        user_token: ctx.accounts.holding_wallet_token_account.to_account_info(),
+        user_lp: ctx.accounts.holding_wallet_lp_token_account.to_account_info(),
+        user: ctx.accounts.holding_wallet.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+    };
+    let mecurial_context = CpiContext::new(
+        ctx.accounts.mercurial_program.to_account_info(),
+        mecurial_accounts,
+    ).with_signer(signer);
+
+    let mut deposited = 0;
+    let mut lp_withdrawn = 0;
+    let mut bounty = 0;
+    if lp_value < target_value {
+        deposited = target_value - lp_value;
+        mercurial_vault::cpi::deposit(mecurial_context, deposited, 0)?;
+
+        let holding_wallet_state = &mut ctx.accounts.holding_wallet_state;
+        holding_wallet_state.principal = holding_wallet_state
+            .principal
+            .checked_add(deposited)
+            .ok_or(NovaError::MathOverflow)?;
+    } else {
+        lp_withdrawn = underlying_to_lp(&ctx.accounts.vault, lp_value - target_value, ctx.accounts.lp_mint.supply)?
+            .min(lp_balance);
+        let underlying_amount = lp_to_underlying(&ctx.accounts.vault, lp_withdrawn, ctx.accounts.lp_mint.supply)?;
+        if !vault_can_pay_out(&ctx.accounts.vault, ctx.accounts.token_vault.amount, underlying_amount) {
+            return err!(NovaError::VaultUnavailable)
+        }
+        let (cost_basis, realised_yield) = ctx.accounts.holding_wallet_state.realise(
+            lp_withdrawn,
+            lp_balance,
+            underlying_amount,
+        );
+        let performance_fee = ctx.accounts.protocol_config.performance_fee(realised_yield);
+        bounty = ctx.accounts.protocol_config
+            .rebalance_bounty(realised_yield)
+            .min(realised_yield.saturating_sub(performance_fee));
+
+        mercurial_vault::cpi::withdraw(mecurial_context, lp_withdrawn, 0)?;
+
+        if performance_fee > 0 {
+            let fee_accounts = Transfer {
+                from: ctx.accounts.holding_wallet_token_account.to_account_info(),
+                to: ctx.accounts.protocol_fee_token_account.to_account_info(),
+                authority: ctx.accounts.holding_wallet.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), fee_accounts)
+                    .with_signer(signer),
+                performance_fee,
+            )?;
+        }
+        if bounty > 0 {
+            let bounty_accounts = Transfer {
+                from: ctx.accounts.holding_wallet_token_account.to_account_info(),
+                to: ctx.accounts.keeper_token_account.to_account_info(),
+                authority: ctx.accounts.holding_wallet.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), bounty_accounts)
+                    .with_signer(signer),
+                bounty,
+            )?;
+        }
+
+        emit!(YieldRealised {
+            payee,
+            organisation_id: _organisation_id,
+            lp_amount: lp_withdrawn,
+            underlying_amount,
+            cost_basis,
+            realised_yield,
+            performance_fee,
+            timestamp: now,
+        });
+    }
+
+    emit!(HoldingWalletRebalanced {
+        holding_wallet_state: ctx.accounts.holding_wallet_state.key(),
+        keeper: ctx.accounts.keeper.key(),
+        drift_bps,
+        deposited,
+        lp_withdrawn,
+        bounty,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct RebalanceHoldingWallet<'info> {
+    #[account(
+        mut,
+        seeds = [b"holding-state", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.bump,
+    )]
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
+    #[account(
+        seeds = [b"holding-wallet", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub holding_wallet: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it only seeds the holding wallet accounts
+    pub payee: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = holding_wallet_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_token_account.mint == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = holding_wallet_lp_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_lp_token_account.mint == lp_mint.key() @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_lp_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        has_one = token_vault,
+        has_one = lp_mint,
+    )]
+    pub vault: Box<Account<'info, mercurial_vault::state::Vault>>,
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+    #[account(
+        mut,
+        constraint = protocol_fee_token_account.owner == protocol_config.fee_recipient @ NovaError::NotFeeRecipientTokenAccount,
+        constraint = protocol_fee_token_account.mint == vault.token_mint @ NovaError::NotFeeRecipientTokenAccount,
+    )]
+    pub protocol_fee_token_account: Box<Account<'info, TokenAccount>>,
+    pub keeper: Signer<'info>,
+    #[account(
+        mut,
+        constraint = keeper_token_account.mint == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub keeper_token_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub mercurial_program: Program<'info, Vault>,
+}
\ No newline at end of file
@@ -0,0 +1,273 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use mercurial_vault::{cpi::accounts::DepositWithdrawLiquidity, program::Vault};
+
+use crate::error::NovaError;
+use crate::events::{StandingOrderExecuted, YieldRealised};
+use crate::state::{HoldingWalletState, ProtocolConfig, StandingOrder};
+use crate::utils::{lp_to_underlying, underlying_to_lp, vault_can_pay_out};
+
+// `destination` must hold the token of the Meteora vault the holding wallet invests in
+pub fn create_standing_order(
+    ctx: Context<CreateStandingOrder>,
+    _organisation_id: String,
+    amount: u64,
+    interval: i64,
+    next_execution_at: i64,
+) -> Result<()> {
+    if amount == 0 || interval <= 0 || next_execution_at < Clock::get()?.unix_timestamp {
+        return err!(NovaError::InvalidStandingOrder)
+    }
+
+    let standing_order = &mut ctx.accounts.standing_order;
+    standing_order.payee = ctx.accounts.payee.key();
+    standing_order.holding_wallet_state = ctx.accounts.holding_wallet_state.key();
+    standing_order.destination = ctx.accounts.destination.key();
+    standing_order.owner = ctx.accounts.employee.key();
+    standing_order.amount = amount;
+    standing_order.interval = interval;
+    standing_order.next_execution_at = next_execution_at;
+    standing_order.execution_count = 0;
+    standing_order.bump = *ctx.bumps.get("standing_order").unwrap();
+
+    Ok(())
+}
+
+pub fn cancel_standing_order(
+    _ctx: Context<CancelStandingOrder>,
+    _organisation_id: String,
+) -> Result<()> {
+    Ok(())
+}
+
+// Permissionless, pays one due execution of the order and schedules the next one after now, so a
+// late execution pays once for all those missed. Orders set up by a previous owner of the holding
+// wallet no longer execute. An idle balance short of the order amount is
+// topped up by unwinding just enough LP from the Meteora vault, the performance fee on the yield
+// realised by the unwind comes out of the idle balance as for any other withdrawal.
+pub fn execute_standing_order(
+    ctx: Context<ExecuteStandingOrder>,
+    _organisation_id: String,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if !ctx.accounts.standing_order.is_due(now) {
+        return err!(NovaError::StandingOrderNotDue)
+    }
+
+    let amount = ctx.accounts.standing_order.amount;
+    let payee = ctx.accounts.standing_order.payee;
+    let signer_seeds = &[
+        b"holding-wallet".as_ref(),
+        payee.as_ref(),
+        _organisation_id.as_bytes(),
+        &[ctx.accounts.holding_wallet_state.wallet_bump],
+    ];
+    let signer: &[&[&[u8]]; 1] = &[&signer_seeds[..]];
+
+    let idle_balance = ctx.accounts.holding_wallet_token_account.amount;
+    let lp_value = lp_to_underlying(
+        &ctx.accounts.vault,
+        ctx.accounts.holding_wallet_lp_token_account.amount,
+        ctx.accounts.lp_mint.supply,
+    )?;
+    ctx.accounts.holding_wallet_state.ensure_unlocked(idle_balance.saturating_add(lp_value), amount, now)?;
+
+    let mut lp_unwound = 0;
+    let mut performance_fee = 0;
+    if idle_balance < amount {
+        let lp_balance = ctx.accounts.holding_wallet_lp_token_account.amount;
+        lp_unwound = underlying_to_lp(&ctx.accounts.vault, amount - idle_balance, ctx.accounts.lp_mint.supply)?
+            .min(lp_balance);
+        let underlying_amount = lp_to_underlying(&ctx.accounts.vault, lp_unwound, ctx.accounts.lp_mint.supply)?;
+        let (cost_basis, realised_yield) = ctx.accounts.holding_wallet_state.realise(
+            lp_unwound,
+            lp_balance,
+            underlying_amount,
+        );
+        performance_fee = ctx.accounts.protocol_config.performance_fee(realised_yield);
+        if idle_balance.saturating_add(underlying_amount) < amount.saturating_add(performance_fee) {
+            return err!(NovaError::NotEnoughBalanceError)
+        }
+        if !vault_can_pay_out(&ctx.accounts.vault, ctx.accounts.token_vault.amount, underlying_amount) {
+            return err!(NovaError::VaultUnavailable)
+        }
+
+        let mecurial_accounts = DepositWithdrawLiquidity {
+            vault: ctx.accounts.vault.to_account_info(),
+            token_vault: ctx.accounts.token_vault.to_account_info(),
+            lp_mint: ctx.accounts.lp_mint.to_account_info(),
+            user_token: ctx.accounts.holding_wallet_token_account.to_account_info(),
+            user_lp: ctx.accounts.holding_wallet_lp_token_account.to_account_info(),
+            user: ctx.accounts.holding_wallet.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        let mecurial_context = CpiContext::new(
+            ctx.accounts.mercurial_program.to_account_info(),
+            mecurial_accounts,
+        ).with_signer(signer);
+        mercurial_vault::cpi::withdraw(mecurial_context, lp_unwound, 0)?;
+
+        if performance_fee > 0 {
+            let fee_accounts = Transfer {
+                from: ctx.accounts.holding_wallet_token_account.to_account_info(),
+                to: ctx.accounts.protocol_fee_token_account.to_account_info(),
+                authority: ctx.accounts.holding_wallet.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), fee_accounts)
+                    .with_signer(signer),
+                performance_fee,
+            )?;
+        }
+
+        emit!(YieldRealised {
+            payee,
+            organisation_id: _organisation_id.clone(),
+            lp_amount: lp_unwound,
+            underlying_amount,
+            cost_basis,
+            realised_yield,
+            performance_fee,
+            timestamp: now,
+        });
+    }
+
+    let transfer_accounts = Transfer {
+        from: ctx.accounts.holding_wallet_token_account.to_account_info(),
+        to: ctx.accounts.destination.to_account_info(),
+        authority: ctx.accounts.holding_wallet.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_accounts)
+            .with_signer(signer),
+        amount,
+    )?;
+
+    let standing_order = &mut ctx.accounts.standing_order;
+    standing_order.schedule_next(now)?;
+    standing_order.execution_count += 1;
+
+    emit!(StandingOrderExecuted {
+        standing_order: standing_order.key(),
+        payee,
+        destination: standing_order.destination,
+        amount,
+        lp_unwound,
+        performance_fee,
+        next_execution_at: standing_order.next_execution_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct CreateStandingOrder<'info> {
+    #[account(
+        seeds = [b"holding-state", payee.key().as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.bump,
+        constraint = holding_wallet_state.owner == employee.key() @ NovaError::NotHoldingWalletOwner,
+    )]
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
+    /// CHECK: This is not dangerous because it only seeds the holding wallet accounts
+    pub payee: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = employee,
+        space = 8 + StandingOrder::SIZE,
+        seeds = [b"standing-order", holding_wallet_state.key().as_ref(), destination.key().as_ref()],
+        bump
+    )]
+    pub standing_order: Account<'info, StandingOrder>,
+    #[account(constraint = destination.mint == vault.token_mint @ NovaError::InvalidStandingOrder)]
+    pub destination: Account<'info, TokenAccount>,
+    pub vault: Box<Account<'info, mercurial_vault::state::Vault>>,
+    #[account(mut)]
+    pub employee: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct CancelStandingOrder<'info> {
+    #[account(
+        mut,
+        close = employee,
+        seeds = [b"standing-order", standing_order.holding_wallet_state.as_ref(), standing_order.destination.as_ref()],
+        bump = standing_order.bump,
+        has_one = holding_wallet_state,
+    )]
+    pub standing_order: Account<'info, StandingOrder>,
+    #[account(constraint = holding_wallet_state.owner == employee.key() @ NovaError::NotHoldingWalletOwner)]
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
+    #[account(mut)]
+    pub employee: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct ExecuteStandingOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"standing-order", holding_wallet_state.key().as_ref(), destination.key().as_ref()],
+        bump = standing_order.bump,
+        has_one = holding_wallet_state,
+        has_one = destination,
+    )]
+    pub standing_order: Box<Account<'info, StandingOrder>>,
+    #[account(
+        mut,
+        seeds = [b"holding-state", standing_order.payee.as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.bump,
+        constraint = holding_wallet_state.owner == standing_order.owner @ NovaError::NotHoldingWalletOwner,
+    )]
+    pub holding_wallet_state: Box<Account<'info, HoldingWalletState>>,
+    #[account(
+        seeds = [b"holding-wallet", standing_order.payee.as_ref(), _organisation_id.as_bytes()],
+        bump = holding_wallet_state.wallet_bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub holding_wallet: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = holding_wallet_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_token_account.mint == vault.token_mint @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = holding_wallet_lp_token_account.owner == holding_wallet.key() @ NovaError::NotHoldingWalletTokenAccount,
+        constraint = holding_wallet_lp_token_account.mint == lp_mint.key() @ NovaError::NotHoldingWalletTokenAccount,
+    )]
+    pub holding_wallet_lp_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = destination.mint == vault.token_mint @ NovaError::InvalidStandingOrder,
+    )]
+    pub destination: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        has_one = token_vault,
+        has_one = lp_mint,
+    )]
+    pub vault: Box<Account<'info, mercurial_vault::state::Vault>>,
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+    #[account(
+        mut,
+        constraint = protocol_fee_token_account.owner == protocol_config.fee_recipient @ NovaError::NotFeeRecipientTokenAccount,
+        constraint = protocol_fee_token_account.mint == vault.token_mint @ NovaError::NotFeeRecipientTokenAccount,
+    )]
+    pub protocol_fee_token_account: Box<Account<'info, TokenAccount>>,
+    pub keeper: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub mercurial_program: Program<'info, Vault>,
+}
\ No newline at end of file
@@ -1,48 +1,294 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{TokenAccount, Token, Transfer, self};
 
-use crate::state::{EmployeeContract, Organisation};
+use crate::state::{
+    ContractStatus, EmployeeContract, Organisation, PayMode, PaymentKind, PaymentReceipt, RateOpening,
+};
 use crate::error::NovaError;
+use crate::events::{AdvanceRecovered, Payslip};
+use crate::instructions::contribution::pay_employer_contributions;
+use crate::instructions::deduction::apply_deductions;
+use crate::instructions::loan::collect_loan_installment;
+use crate::instructions::payout::distribute_payout;
+use crate::oracle::{load_contract_quote, to_payout_mint};
+use crate::token_interface::{is_token_program, token_account, transfer_checked, TOKEN_2022_PROGRAM_ID};
+use crate::utils::{bps_of, transfer_from_streaming_wallet};
 
-pub fn pay_contract(
-    ctx: Context<PayContract>,
+const SECONDS_PER_DAY: u128 = 86_400;
+
+pub fn pay_contract<'info>(
+    ctx: Context<'_, '_, '_, 'info, PayContract<'info>>,
     _organisation_id: String,
     _employee_id: String,
     amount: u64,
+    payment_kind: PaymentKind,
+    rate_opening: Option<RateOpening>,
 ) -> Result<()> {
-    let balance = ctx.accounts.streaming_wallet_token_account.amount;
-    if balance < amount {
+    if ctx.accounts.employee_contract.pay_mode == PayMode::Hourly && payment_kind == PaymentKind::Salary {
+        return err!(NovaError::HourlyContractRequiresTimesheet)
+    }
+
+    let receipt_bump = *ctx.bumps.get("receipt").unwrap();
+    settle_payment(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        receipt_bump,
+        &_organisation_id,
+        amount,
+        payment_kind,
+        rate_opening.as_ref(),
+        false,
+        0,
+    )?;
+
+    Ok(())
+}
+
+// Pays `amount` through the contract's deductions, contributions and recoveries, writes the
+// receipt and returns the payment index. Contracts with a committed rate need `rate_opening`.
+// `after_termination` lets work done before a termination still be paid once it took effect.
+// `retained_percentage` of the net pay goes to the employee token account, a holding wallet,
+// before the payee's payout instructions split the rest.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_payment<'info>(
+    accounts: &mut PayContract<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    receipt_bump: u8,
+    organisation_id: &str,
+    amount: u64,
+    payment_kind: PaymentKind,
+    rate_opening: Option<&RateOpening>,
+    after_termination: bool,
+    retained_percentage: u8,
+) -> Result<u64> {
+    let now = Clock::get()?.unix_timestamp;
+    let rate = accounts.employee_contract.open_rate(rate_opening)?;
+
+    // Remaining accounts: the price feed for contracts not denominated in USD, the admin
+    // approvers required by the payment policy, then for taxable payments the deduction schedule
+    // and its destinations, the contribution schedule and its destinations, and the loan and its
+    // lender, and last the payee's payout instructions and their destinations
+    let mut remaining_accounts = remaining_accounts.iter();
+    let quote = load_contract_quote(&mut remaining_accounts, &accounts.employee_contract, &accounts.organisation, now)?;
+    let (fx_price, fx_expo) = quote.as_ref().map_or((1, 0), |quote| (quote.price, quote.expo));
+    let to_payout_mint = |value: u64| to_payout_mint(quote.as_ref(), value);
+
+    // Salary is earned net of any approved leave beyond the contract's leave balance, valued in
+    // the contract's currency
+    let contract_amount = amount;
+    let mut unpaid_leave_withheld = 0;
+    if payment_kind.follows_rate_schedule() && accounts.employee_contract.status == ContractStatus::Active {
+        let employee_contract = &mut accounts.employee_contract;
+        employee_contract.accrue_leave(now);
+        if employee_contract.pay_mode == PayMode::Salaried {
+            unpaid_leave_withheld = employee_contract.withhold_unpaid_leave(rate, contract_amount);
+        }
+    }
+    let amount = to_payout_mint(contract_amount)?;
+    let earned = to_payout_mint(contract_amount - unpaid_leave_withheld)?;
+    let unpaid_leave_withheld = amount - earned;
+
+    let balance = token_account(&accounts.streaming_wallet_token_account)?.amount;
+    let employer_contribution = if payment_kind.is_taxable() {
+        bps_of(amount, accounts.employee_contract.employer_contribution_bps)
+    } else {
+        0
+    };
+    if balance < amount.saturating_add(employer_contribution) {
         return err!(NovaError::NotEnoughBalanceError)
     }
 
-    if ctx.accounts.organisation.stream_authority != *ctx.accounts.payer.to_account_info().key {
+    if accounts.organisation.stream_authority != *accounts.payer.to_account_info().key {
         return err!(NovaError::PayerIsNotStreamAuthority)
     }
 
-    if ctx.accounts.employee_contract.payee != *ctx.accounts.employee_token_account.to_account_info().key {
+    // `payee` is the employee's wallet, so the token account is matched by its owner. Comparing it
+    // with the token account's own address only passed when the payee key was a token account.
+    if !accounts.employee_contract.is_payee_token_account_owner(&token_account(&accounts.employee_token_account)?.owner, organisation_id) {
         return err!(NovaError::NotEmployeeTokenAccount)
     }
 
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.streaming_wallet_token_account.to_account_info(),
-        to: ctx.accounts.employee_token_account.to_account_info(),
-        authority: ctx.accounts.streaming_wallet.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-
-    let signer_seeds = &[
-        b"streaming-wallet",
-        _organisation_id.as_bytes(),
-        &[ctx.accounts.organisation.stream_wallet_bump],
-    ];
-    let signer = &[&signer_seeds[..]];
-
-    token::transfer(
-        CpiContext::new(cpi_program, cpi_accounts).with_signer(
-            signer
-        ),
-        amount,
-    )?;
+    let status = accounts.employee_contract.status;
+    if status != ContractStatus::Active && !(after_termination && status == ContractStatus::Terminated) {
+        return err!(NovaError::ContractNotActive)
+    }
+
+    let token_program = accounts.token_program.to_account_info();
+    let streaming_wallet = accounts.streaming_wallet.to_account_info();
+    let streaming_wallet_token_account = accounts.streaming_wallet_token_account.to_account_info();
+    let mint = accounts.mint.to_account_info();
+    let stream_wallet_bump = accounts.organisation.stream_wallet_bump;
+
+    let required_approvals = accounts.organisation.payment_policy(payment_kind).approvals_for(amount);
+    verify_admin_approvals(&mut remaining_accounts, &accounts.organisation, required_approvals)?;
+
+    let mut deductions = Vec::new();
+    let mut employer_contributions = Vec::new();
+    let mut recovered_advance = 0;
+    let mut loan_installment = 0;
+    if payment_kind.is_taxable() {
+        deductions = apply_deductions(
+            &mut remaining_accounts,
+            &accounts.employee_contract,
+            token_program.clone(),
+            streaming_wallet.clone(),
+            streaming_wallet_token_account.clone(),
+            mint.clone(),
+            organisation_id,
+            stream_wallet_bump,
+            quote.as_ref(),
+            earned,
+        )?;
+    }
+    let total_deductions: u64 = deductions.iter().map(|line| line.amount).sum();
+
+    if payment_kind.is_taxable() {
+        employer_contributions = pay_employer_contributions(
+            &mut remaining_accounts,
+            &mut accounts.employee_contract,
+            token_program.clone(),
+            streaming_wallet.clone(),
+            streaming_wallet_token_account.clone(),
+            mint.clone(),
+            organisation_id,
+            stream_wallet_bump,
+            earned,
+        )?;
+
+        let employee_contract = &mut accounts.employee_contract;
+        recovered_advance = employee_contract.recover_advance(earned - total_deductions);
+        if recovered_advance > 0 {
+            emit!(AdvanceRecovered {
+                employee_contract: employee_contract.key(),
+                amount: recovered_advance,
+                outstanding_advance: employee_contract.outstanding_advance,
+            });
+        }
+
+        loan_installment = collect_loan_installment(
+            &mut remaining_accounts,
+            &mut accounts.employee_contract,
+            token_program.clone(),
+            streaming_wallet.clone(),
+            streaming_wallet_token_account.clone(),
+            mint.clone(),
+            organisation_id,
+            stream_wallet_bump,
+            earned - total_deductions - recovered_advance,
+            payment_kind.is_final_pay(),
+        )?;
+    }
+    let total_employer_contributions: u64 = employer_contributions.iter().map(|line| line.amount).sum();
+    let net_amount = earned - total_deductions - recovered_advance - loan_installment;
+    let mut transfer_fee = 0;
+
+    // Only what lands in the payee's holding wallet can later be clawed back from it
+    let employee_token_account = token_account(&accounts.employee_token_account)?;
+    let holding_wallet_balance = (employee_token_account.owner == accounts.employee_contract.holding_wallet(organisation_id))
+        .then_some(employee_token_account.amount);
+
+    let retained_amount = net_amount * retained_percentage as u64 / 100;
+    if retained_amount > 0 {
+        if holding_wallet_balance.is_none() {
+            return err!(NovaError::NotHoldingWalletTokenAccount)
+        }
+        if *mint.owner == TOKEN_2022_PROGRAM_ID {
+            return err!(NovaError::UnsupportedHoldingWalletMint)
+        }
+    }
+
+    if net_amount > 0 {
+        let signer_seeds = &[
+            b"streaming-wallet",
+            organisation_id.as_bytes(),
+            &[stream_wallet_bump],
+        ];
+        if retained_amount > 0 {
+            transfer_checked(
+                token_program.clone(),
+                streaming_wallet_token_account.clone(),
+                mint.clone(),
+                accounts.employee_token_account.to_account_info(),
+                streaming_wallet.clone(),
+                &[&signer_seeds[..]],
+                retained_amount,
+            )?;
+        }
+        if net_amount > retained_amount {
+            transfer_fee = distribute_payout(
+                &mut remaining_accounts,
+                &accounts.employee_contract.payee,
+                organisation_id,
+                token_program,
+                streaming_wallet,
+                streaming_wallet_token_account,
+                mint,
+                accounts.employee_token_account.to_account_info(),
+                &[&signer_seeds[..]],
+                net_amount - retained_amount,
+            )?;
+        }
+    }
+
+    let employee_contract = &mut accounts.employee_contract;
+    if payment_kind.follows_rate_schedule() {
+        employee_contract.last_paid_at = now;
+    }
+    let payment_index = employee_contract.payment_count;
+    employee_contract.payment_count += 1;
+
+    let receipt = &mut accounts.receipt;
+    receipt.employee_contract = employee_contract.key();
+    receipt.payment_index = payment_index;
+    receipt.kind = payment_kind;
+    receipt.gross_amount = earned;
+    receipt.total_deductions = total_deductions;
+    receipt.employer_contributions = total_employer_contributions;
+    receipt.net_amount = net_amount - transfer_fee;
+    receipt.payer = accounts.payer.key();
+    receipt.paid_at = now;
+    receipt.bump = receipt_bump;
+    receipt.currency = accounts.employee_contract.currency;
+    receipt.contract_amount = contract_amount;
+    receipt.fx_price = fx_price;
+    receipt.fx_expo = fx_expo;
+    receipt.transfer_fee = transfer_fee;
+    if let Some(holding_wallet_balance) = holding_wallet_balance {
+        receipt.holding_wallet_token_account = accounts.employee_token_account.key();
+        receipt.holding_wallet_amount = token_account(&accounts.employee_token_account)?.amount.saturating_sub(holding_wallet_balance);
+    }
+
+    emit!(Payslip {
+        employee_contract: accounts.employee_contract.key(),
+        payment_index,
+        payment_kind,
+        gross_amount: earned,
+        deductions,
+        advance_recovered: recovered_advance,
+        loan_installment,
+        unpaid_leave_withheld,
+        transfer_fee,
+        net_amount: net_amount - transfer_fee,
+        employer_contributions,
+        timestamp: now,
+    });
+
+    Ok(payment_index)
+}
+
+// Consumes `required_approvals` remaining accounts, each of which must be a distinct admin signer
+pub fn verify_admin_approvals(
+    remaining_accounts: &mut std::slice::Iter<AccountInfo>,
+    organisation: &Organisation,
+    required_approvals: u8,
+) -> Result<()> {
+    let mut approvers: Vec<Pubkey> = Vec::new();
+    for _ in 0..required_approvals {
+        let approver = remaining_accounts.next().ok_or(NovaError::MissingApprovals)?;
+        if !approver.is_signer || !organisation.is_admin(approver.key) || approvers.contains(approver.key) {
+            return err!(NovaError::MissingApprovals)
+        }
+        approvers.push(*approver.key);
+    }
 
     Ok(())
 }
@@ -52,35 +298,25 @@ pub fn withdraw_from_stream_wallet(
     _organisation_id: String,
     amount: u64,
 ) -> Result<()> {
-    let balance = ctx.accounts.streaming_wallet_token_account.amount;
+    let balance = token_account(&ctx.accounts.streaming_wallet_token_account)?.amount;
     if balance < amount {
         panic!("Not enough balance")
     }
 
     let payer_key = ctx.accounts.payer.to_account_info().key;
     let admins = ctx.accounts.organisation.admins.clone();
-    if !admins.contains(&payer_key) {
+    if !admins.contains(payer_key) {
         panic!("Payer is not an admin")
     }
 
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.streaming_wallet_token_account.to_account_info(),
-        to: ctx.accounts.withdrawee_token_account.to_account_info(),
-        authority: ctx.accounts.streaming_wallet.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-
-    let signer_seeds = &[
-        b"streaming-wallet",
-        _organisation_id.as_bytes(),
-        &[ctx.accounts.organisation.stream_wallet_bump],
-    ];
-    let signer = &[&signer_seeds[..]];
-
-    token::transfer(
-        CpiContext::new(cpi_program, cpi_accounts).with_signer(
-            signer
-        ),
+    transfer_from_streaming_wallet(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.streaming_wallet.to_account_info(),
+        ctx.accounts.streaming_wallet_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.withdrawee_token_account.to_account_info(),
+        &_organisation_id,
+        ctx.accounts.organisation.stream_wallet_bump,
         amount,
     )?;
 
@@ -88,6 +324,65 @@ pub fn withdraw_from_stream_wallet(
 }
 
 
+// Remaining accounts: the `EmployeeContract` of each of `employee_ids` in the same order, each
+// contract of the organisation at most once. `rate_openings` follows the same order and must open
+// the rate of every contract with a committed rate.
+pub fn get_funding_runway(
+    ctx: Context<GetFundingRunway>,
+    _organisation_id: String,
+    employee_ids: Vec<String>,
+    rate_openings: Vec<Option<RateOpening>>,
+) -> Result<FundingRunway> {
+    if employee_ids.len() != ctx.remaining_accounts.len() || rate_openings.len() != employee_ids.len() {
+        return err!(NovaError::InvalidEmployeeContract)
+    }
+
+    let mut payroll_cost_per_day: u128 = 0;
+    let mut employer_contributions_per_day: u128 = 0;
+    for (index, (employee_id, contract_info)) in employee_ids.iter().zip(ctx.remaining_accounts.iter()).enumerate() {
+        if ctx.remaining_accounts[..index].iter().any(|counted| counted.key == contract_info.key) {
+            return err!(NovaError::InvalidEmployeeContract)
+        }
+        let contract: Account<EmployeeContract> = Account::try_from(contract_info)?;
+        let contract_address = Pubkey::create_program_address(
+            &[b"employee-contract", _organisation_id.as_bytes(), employee_id.as_bytes(), &[contract.bump]],
+            &crate::ID,
+        ).map_err(|_| error!(NovaError::InvalidEmployeeContract))?;
+        if contract_address != contract_info.key() {
+            return err!(NovaError::InvalidEmployeeContract)
+        }
+        if contract.status != ContractStatus::Active {
+            continue;
+        }
+        let rate = contract.open_rate(rate_openings[index].as_ref())?;
+        let pay_period = contract.pay_period.max(1) as u128;
+        payroll_cost_per_day += rate as u128 * SECONDS_PER_DAY / pay_period;
+        employer_contributions_per_day +=
+            (contract.cost_per_period(rate) - rate) as u128 * SECONDS_PER_DAY / pay_period;
+    }
+
+    let balance = token_account(&ctx.accounts.streaming_wallet_token_account)?.amount;
+    let total_cost_per_day = payroll_cost_per_day + employer_contributions_per_day;
+    let runway_days = (balance as u128)
+        .checked_div(total_cost_per_day)
+        .map_or(u64::MAX, |days| days as u64);
+
+    Ok(FundingRunway {
+        balance,
+        payroll_cost_per_day: payroll_cost_per_day as u64,
+        employer_contributions_per_day: employer_contributions_per_day as u64,
+        runway_days,
+    })
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FundingRunway {
+    pub balance: u64,
+    pub payroll_cost_per_day: u64,
+    pub employer_contributions_per_day: u64,
+    pub runway_days: u64,
+}
+
 #[derive(Accounts)]
 #[instruction(_organisation_id: String, employee_id: String)]
 pub struct PayContract<'info> {
@@ -98,7 +393,7 @@ pub struct PayContract<'info> {
     )]
     pub employee_contract: Account<'info, EmployeeContract>,
     #[account(
-        seeds = [b"organisation", _organisation_id.as_bytes().as_ref()], 
+        seeds = [b"organisation", _organisation_id.as_bytes()], 
         bump=organisation.bump,
     )]
     pub organisation: Account<'info, Organisation>,
@@ -110,20 +405,37 @@ pub struct PayContract<'info> {
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub streaming_wallet: AccountInfo<'info>,
     #[account(mut)]
-    pub streaming_wallet_token_account: Account<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub streaming_wallet_token_account: AccountInfo<'info>,
     #[account(mut)]
-    pub employee_token_account: Account<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub employee_token_account: AccountInfo<'info>,
+    #[account(
+        constraint = token_account(&streaming_wallet_token_account)?.mint == mint.key() @ NovaError::NotStreamingWalletTokenAccount,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub mint: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PaymentReceipt::SIZE,
+        seeds = [b"payment-receipt", employee_contract.key().as_ref(), &employee_contract.payment_count.to_le_bytes()],
+        bump
+    )]
+    pub receipt: Account<'info, PaymentReceipt>,
     #[account(mut)]
     pub payer: Signer<'info>,
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    pub token_program: Program<'info, Token>, 
+    #[account(constraint = is_token_program(token_program.key) @ NovaError::InvalidTokenProgram)]
+    /// CHECK: This is not dangerous because it must be one of the token programs
+    pub token_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 #[instruction(_organisation_id: String)]
 pub struct WithdrawFromStreamWallet<'info> {
     #[account(
-        seeds = [b"organisation", _organisation_id.as_bytes().as_ref()], 
+        seeds = [b"organisation", _organisation_id.as_bytes()], 
         bump=organisation.bump,
     )]
     pub organisation: Account<'info, Organisation>,
@@ -135,11 +447,37 @@ pub struct WithdrawFromStreamWallet<'info> {
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub streaming_wallet: AccountInfo<'info>,
     #[account(mut)]
-    pub streaming_wallet_token_account: Account<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub streaming_wallet_token_account: AccountInfo<'info>,
     #[account(mut)]
-    pub withdrawee_token_account: Account<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub withdrawee_token_account: AccountInfo<'info>,
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub mint: AccountInfo<'info>,
     #[account(mut)]
     pub payer: Signer<'info>,
+    #[account(constraint = is_token_program(token_program.key) @ NovaError::InvalidTokenProgram)]
+    /// CHECK: This is not dangerous because it must be one of the token programs
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String)]
+pub struct GetFundingRunway<'info> {
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Account<'info, Organisation>,
+    #[account(
+        seeds = [b"streaming-wallet", _organisation_id.as_bytes()],
+        bump = organisation.stream_wallet_bump,
+    )]
     /// CHECK: This is not dangerous because we don't read or write from this account
-    pub token_program: Program<'info, Token>, 
+    pub streaming_wallet: AccountInfo<'info>,
+    #[account(
+        constraint = token_account(&streaming_wallet_token_account)?.owner == streaming_wallet.key() @ NovaError::NotStreamingWalletTokenAccount,
+    )]
+    /// CHECK: This is not dangerous because it is deserialised through `token_interface`
+    pub streaming_wallet_token_account: AccountInfo<'info>,
 }
\ No newline at end of file
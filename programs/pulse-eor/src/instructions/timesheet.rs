@@ -0,0 +1,252 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::events::TimesheetUpdated;
+use crate::instructions::stream::*;
+use crate::state::{
+    ContractStatus, EmployeeContract, Organisation, PayMode, PaymentKind, RateOpening, Timesheet,
+    TimesheetStatus,
+};
+
+pub fn submit_timesheet(
+    ctx: Context<SubmitTimesheet>,
+    _organisation_id: String,
+    _employee_id: String,
+    period_start: i64,
+    period_end: i64,
+    hours: u32,
+) -> Result<()> {
+    let employee_contract = &ctx.accounts.employee_contract;
+    if employee_contract.status != ContractStatus::Active {
+        return err!(NovaError::ContractNotActive)
+    }
+    if employee_contract.pay_mode != PayMode::Hourly {
+        return err!(NovaError::ContractNotHourly)
+    }
+    if period_end <= period_start || hours == 0 {
+        return err!(NovaError::InvalidTimesheet)
+    }
+    // No more hours can be logged than the period spans
+    if hours as i64 > period_end.saturating_sub(period_start) / 3600 {
+        return err!(NovaError::InvalidTimesheet)
+    }
+    if period_start < employee_contract.last_timesheet_end {
+        return err!(NovaError::OverlappingTimesheet)
+    }
+    ctx.accounts.employee_contract.last_timesheet_end = period_end;
+
+    let now = Clock::get()?.unix_timestamp;
+    let timesheet = &mut ctx.accounts.timesheet;
+    timesheet.employee_contract = ctx.accounts.employee_contract.key();
+    timesheet.period_start = period_start;
+    timesheet.period_end = period_end;
+    timesheet.hours = hours;
+    timesheet.status = TimesheetStatus::Submitted;
+    timesheet.payout = 0;
+    timesheet.approver = Pubkey::default();
+    timesheet.submitted_at = now;
+    timesheet.payment_index = 0;
+    timesheet.bump = *ctx.bumps.get("timesheet").unwrap();
+
+    emit!(TimesheetUpdated {
+        timesheet: timesheet.key(),
+        employee_contract: timesheet.employee_contract,
+        status: TimesheetStatus::Submitted,
+        hours,
+        payout: 0,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn approve_timesheet(
+    ctx: Context<ApproveTimesheet>,
+    _organisation_id: String,
+    _employee_id: String,
+    _period_start: i64,
+    rate_opening: Option<RateOpening>,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.timesheet.status != TimesheetStatus::Submitted {
+        return err!(NovaError::InvalidTimesheet)
+    }
+
+    let rate = ctx.accounts.employee_contract.open_rate(rate_opening.as_ref())?;
+    let payout = (ctx.accounts.timesheet.hours as u64)
+        .checked_mul(rate)
+        .ok_or(NovaError::MathOverflow)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let timesheet = &mut ctx.accounts.timesheet;
+    timesheet.status = TimesheetStatus::Approved;
+    timesheet.payout = payout;
+    timesheet.approver = ctx.accounts.admin.key();
+
+    emit!(TimesheetUpdated {
+        timesheet: timesheet.key(),
+        employee_contract: timesheet.employee_contract,
+        status: TimesheetStatus::Approved,
+        hours: timesheet.hours,
+        payout,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Rejected timesheets are closed so the worker can resubmit the period, which reopens it for
+// submission when it is the latest one
+pub fn reject_timesheet(
+    ctx: Context<RejectTimesheet>,
+    _organisation_id: String,
+    _employee_id: String,
+    _period_start: i64,
+) -> Result<()> {
+    if !ctx.accounts.organisation.is_admin(ctx.accounts.admin.key) {
+        return err!(NovaError::PayerIsNotAdmin)
+    }
+    if ctx.accounts.timesheet.status != TimesheetStatus::Submitted {
+        return err!(NovaError::InvalidTimesheet)
+    }
+
+    let timesheet = &ctx.accounts.timesheet;
+    let employee_contract = &mut ctx.accounts.employee_contract;
+    if employee_contract.last_timesheet_end == timesheet.period_end {
+        employee_contract.last_timesheet_end = timesheet.period_start;
+    }
+
+    Ok(())
+}
+
+// Remaining accounts follow `pay_contract`. Timesheets approved before the contract was
+// terminated can still be paid after it.
+pub fn pay_timesheet<'info>(
+    ctx: Context<'_, '_, '_, 'info, PayTimesheet<'info>>,
+    _organisation_id: String,
+    _employee_id: String,
+    _period_start: i64,
+    rate_opening: Option<RateOpening>,
+) -> Result<()> {
+    if ctx.accounts.timesheet.status != TimesheetStatus::Approved {
+        return err!(NovaError::InvalidTimesheet)
+    }
+
+    let payout = ctx.accounts.timesheet.payout;
+    let receipt_bump = *ctx.bumps.get("receipt").unwrap();
+    let payment_index = settle_payment(
+        &mut ctx.accounts.pay,
+        ctx.remaining_accounts,
+        receipt_bump,
+        &_organisation_id,
+        payout,
+        PaymentKind::Salary,
+        rate_opening.as_ref(),
+        true,
+        0,
+    )?;
+
+    let timesheet = &mut ctx.accounts.timesheet;
+    timesheet.status = TimesheetStatus::Paid;
+    timesheet.payment_index = payment_index;
+
+    emit!(TimesheetUpdated {
+        timesheet: timesheet.key(),
+        employee_contract: timesheet.employee_contract,
+        status: TimesheetStatus::Paid,
+        hours: timesheet.hours,
+        payout,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, period_start: i64)]
+pub struct SubmitTimesheet<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+        constraint = employee_contract.payee == payee.key() @ NovaError::NotContractPayee,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        init,
+        payer = payee,
+        space = 8 + Timesheet::SIZE,
+        seeds = [b"timesheet", employee_contract.key().as_ref(), &period_start.to_le_bytes()],
+        bump
+    )]
+    pub timesheet: Box<Account<'info, Timesheet>>,
+    #[account(mut)]
+    pub payee: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, _period_start: i64)]
+pub struct ApproveTimesheet<'info> {
+    #[account(
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        mut,
+        seeds = [b"timesheet", employee_contract.key().as_ref(), &_period_start.to_le_bytes()],
+        bump = timesheet.bump,
+    )]
+    pub timesheet: Box<Account<'info, Timesheet>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, _period_start: i64)]
+pub struct RejectTimesheet<'info> {
+    #[account(
+        mut,
+        seeds = [b"employee-contract", _organisation_id.as_bytes(), _employee_id.as_bytes()],
+        bump = employee_contract.bump,
+    )]
+    pub employee_contract: Box<Account<'info, EmployeeContract>>,
+    #[account(
+        mut,
+        close = payee,
+        seeds = [b"timesheet", employee_contract.key().as_ref(), &_period_start.to_le_bytes()],
+        bump = timesheet.bump,
+    )]
+    pub timesheet: Box<Account<'info, Timesheet>>,
+    #[account(
+        seeds = [b"organisation", _organisation_id.as_bytes()],
+        bump = organisation.bump,
+    )]
+    pub organisation: Box<Account<'info, Organisation>>,
+    #[account(
+        mut,
+        constraint = payee.key() == employee_contract.payee @ NovaError::NotContractPayee,
+    )]
+    /// CHECK: This is not dangerous because we only refund rent to the contract payee
+    pub payee: AccountInfo<'info>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_organisation_id: String, _employee_id: String, _period_start: i64)]
+pub struct PayTimesheet<'info> {
+    pub pay: PayContract<'info>,
+    #[account(
+        mut,
+        seeds = [b"timesheet", pay.employee_contract.key().as_ref(), &_period_start.to_le_bytes()],
+        bump = timesheet.bump,
+    )]
+    pub timesheet: Box<Account<'info, Timesheet>>,
+}
\ No newline at end of file
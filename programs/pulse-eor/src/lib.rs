@@ -1,11 +1,16 @@
 use anchor_lang::prelude::*;
 use instructions::*;
+use state::{ContributionRule, Currency, DeductionRule, ExpenseCategory, PayMode, PaymentKind, PayoutDestination, RateOpening, TerminationReason};
 
 declare_id!("DafQCS2iwRB48xvjYa1Nsxz5wFDuMXq37qCmy4Sov8ce");
 
 pub mod instructions;
 pub mod state;
 pub mod error;
+pub mod events;
+pub mod utils;
+pub mod oracle;
+pub mod token_interface;
 
 #[program]
 pub mod pulse_eor {
@@ -19,18 +24,19 @@ pub mod pulse_eor {
         instructions::employee::setup_holding_wallet(ctx, _organisation_id)
     }
 
-    pub fn pay_organisation_employee(
-        ctx: Context<PayOrganisationEmployee>,
+    pub fn pay_organisation_employee<'info>(
+        ctx: Context<'_, '_, '_, 'info, PayOrganisationEmployee<'info>>,
         _organisation_id: String,
         _employee_id: String,
         _amount: u64,
+        rate_opening: Option<RateOpening>,
     ) -> Result<()> {
         print!("Paying organisation employee");
-        instructions::organisation::pay_organisation_employee(ctx, _organisation_id, _amount)
+        instructions::organisation::pay_organisation_employee(ctx, _organisation_id, _amount, rate_opening)
     }
 
-    pub fn employee_withdraw(
-        ctx: Context<EmployeeWithdraw>,
+    pub fn employee_withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, EmployeeWithdraw<'info>>,
         _organisation_id: String,
         amount: u64,
         virtual_price: f64,
@@ -39,6 +45,14 @@ pub mod pulse_eor {
         instructions::employee::employee_withdraw(ctx, _organisation_id, amount, virtual_price)
     }
 
+    pub fn set_payout_instructions(
+        ctx: Context<SetPayoutInstructions>,
+        destinations: Vec<PayoutDestination>,
+    ) -> Result<()> {
+        print!("Setting payout instructions");
+        instructions::payout::set_payout_instructions(ctx, destinations)
+    }
+
     pub fn adjust_meteora_allocation(
         ctx: Context<AdjustMeteoraAllocation>,
         _organisation_id: String,
@@ -50,6 +64,159 @@ pub mod pulse_eor {
         instructions::employee::adjust_meteora_allocation(ctx, _organisation_id, meteora_allocation, amount_to_withdraw_from_mercurial, amount_to_deposit_to_mercurial)
     }
     
+    pub fn get_holding_balance(
+        ctx: Context<GetHoldingBalance>,
+        _organisation_id: String,
+    ) -> Result<HoldingBalance> {
+        print!("Getting holding balance");
+        instructions::employee::get_holding_balance(ctx, _organisation_id)
+    }
+
+    pub fn set_holding_delegate(
+        ctx: Context<ManageHoldingAuthority>,
+        _organisation_id: String,
+        delegate: Pubkey,
+        delegate_limit: u64,
+        delegate_period: i64,
+    ) -> Result<()> {
+        print!("Setting holding delegate");
+        instructions::holding_authority::set_holding_delegate(ctx, _organisation_id, delegate, delegate_limit, delegate_period)
+    }
+
+    pub fn set_guardians(
+        ctx: Context<ManageHoldingAuthority>,
+        _organisation_id: String,
+        guardians: Vec<Pubkey>,
+        guardian_threshold: u8,
+    ) -> Result<()> {
+        print!("Setting guardians");
+        instructions::holding_authority::set_guardians(ctx, _organisation_id, guardians, guardian_threshold)
+    }
+
+    pub fn recover_holding_wallet(
+        ctx: Context<RecoverHoldingWallet>,
+        _organisation_id: String,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        print!("Recovering holding wallet");
+        instructions::holding_authority::recover_holding_wallet(ctx, _organisation_id, new_owner)
+    }
+
+    pub fn complete_holding_wallet_recovery(
+        ctx: Context<RecoverHoldingWallet>,
+        _organisation_id: String,
+    ) -> Result<()> {
+        print!("Completing holding wallet recovery");
+        instructions::holding_authority::complete_holding_wallet_recovery(ctx, _organisation_id)
+    }
+
+    pub fn cancel_holding_wallet_recovery(
+        ctx: Context<ManageHoldingAuthority>,
+        _organisation_id: String,
+    ) -> Result<()> {
+        print!("Cancelling holding wallet recovery");
+        instructions::holding_authority::cancel_holding_wallet_recovery(ctx, _organisation_id)
+    }
+
+    pub fn rebalance_holding_wallet(
+        ctx: Context<RebalanceHoldingWallet>,
+        _organisation_id: String,
+    ) -> Result<()> {
+        print!("Rebalancing holding wallet");
+        instructions::rebalance::rebalance_holding_wallet(ctx, _organisation_id)
+    }
+
+    pub fn migrate_organisation(ctx: Context<MigrateAccount>) -> Result<()> {
+        print!("Migrating organisation");
+        instructions::migration::migrate_organisation(ctx)
+    }
+
+    pub fn migrate_employee_contract(
+        ctx: Context<MigrateEmployeeContract>,
+        _organisation_id: String,
+        _employee_id: String,
+        pay_period: i64,
+    ) -> Result<()> {
+        print!("Migrating employee contract");
+        instructions::migration::migrate_employee_contract(ctx, _organisation_id, _employee_id, pay_period)
+    }
+
+    pub fn migrate_holding_wallet_state(
+        ctx: Context<MigrateHoldingWalletState>,
+        _organisation_id: String,
+    ) -> Result<()> {
+        print!("Migrating holding wallet state");
+        instructions::migration::migrate_holding_wallet_state(ctx, _organisation_id)
+    }
+
+    pub fn sweep_legacy_holding_wallet(
+        ctx: Context<SweepLegacyHoldingWallet>,
+        _organisation_id: String,
+    ) -> Result<()> {
+        print!("Sweeping legacy holding wallet");
+        instructions::migration::sweep_legacy_holding_wallet(ctx, _organisation_id)
+    }
+
+    pub fn migrate_protocol_config(ctx: Context<MigrateAccount>) -> Result<()> {
+        print!("Migrating protocol config");
+        instructions::migration::migrate_protocol_config(ctx)
+    }
+
+    pub fn emergency_unwind<'info>(
+        ctx: Context<'_, '_, '_, 'info, EmergencyUnwind<'info>>,
+        _organisation_id: String,
+    ) -> Result<()> {
+        print!("Unwinding holding wallets");
+        instructions::emergency::emergency_unwind(ctx, _organisation_id)
+    }
+
+    pub fn lock_funds(
+        ctx: Context<LockFunds>,
+        _organisation_id: String,
+        name: String,
+        amount: u64,
+        unlock_at: i64,
+    ) -> Result<()> {
+        print!("Locking funds");
+        instructions::lock::lock_funds(ctx, _organisation_id, name, amount, unlock_at)
+    }
+
+    pub fn release_lock(
+        ctx: Context<ReleaseLock>,
+        _organisation_id: String,
+        name: String,
+    ) -> Result<()> {
+        print!("Releasing lock");
+        instructions::lock::release_lock(ctx, _organisation_id, name)
+    }
+
+    pub fn create_standing_order(
+        ctx: Context<CreateStandingOrder>,
+        _organisation_id: String,
+        amount: u64,
+        interval: i64,
+        next_execution_at: i64,
+    ) -> Result<()> {
+        print!("Creating standing order");
+        instructions::standing_order::create_standing_order(ctx, _organisation_id, amount, interval, next_execution_at)
+    }
+
+    pub fn cancel_standing_order(
+        ctx: Context<CancelStandingOrder>,
+        _organisation_id: String,
+    ) -> Result<()> {
+        print!("Cancelling standing order");
+        instructions::standing_order::cancel_standing_order(ctx, _organisation_id)
+    }
+
+    pub fn execute_standing_order(
+        ctx: Context<ExecuteStandingOrder>,
+        _organisation_id: String,
+    ) -> Result<()> {
+        print!("Executing standing order");
+        instructions::standing_order::execute_standing_order(ctx, _organisation_id)
+    }
+
     pub fn setup_organisation(
         ctx: Context<SetupOrganisation>,
         _organisation_id: String,
@@ -63,19 +230,458 @@ pub mod pulse_eor {
         _organisation_id: String,
         _employee_id: String,
         rate: u64,
+        pay_period: i64,
+        pay_mode: PayMode,
+        rate_commitment: [u8; 32],
     ) -> Result<()> {
         print!("Setting up employee contract");
-        instructions::organisation::setup_employee_contract(ctx, _organisation_id, _employee_id, rate)
+        instructions::organisation::setup_employee_contract(ctx, _organisation_id, _employee_id, rate, pay_period, pay_mode, rate_commitment)
+    }
+
+    pub fn configure_advances(
+        ctx: Context<ConfigureAdvances>,
+        _organisation_id: String,
+        advance_limit_bps: u16,
+        advance_fee: u64,
+    ) -> Result<()> {
+        print!("Configuring advances");
+        instructions::advance::configure_advances(ctx, _organisation_id, advance_limit_bps, advance_fee)
+    }
+
+    pub fn request_advance(
+        ctx: Context<RequestAdvance>,
+        _organisation_id: String,
+        _employee_id: String,
+        amount: u64,
+        rate_opening: Option<RateOpening>,
+    ) -> Result<()> {
+        print!("Requesting advance");
+        instructions::advance::request_advance(ctx, _organisation_id, _employee_id, amount, rate_opening)
+    }
+
+    pub fn configure_payment_policy(
+        ctx: Context<ConfigurePaymentPolicy>,
+        _organisation_id: String,
+        payment_kind: PaymentKind,
+        approval_threshold: u64,
+        required_approvals: u8,
+    ) -> Result<()> {
+        print!("Configuring payment policy");
+        instructions::organisation::configure_payment_policy(ctx, _organisation_id, payment_kind, approval_threshold, required_approvals)
+    }
+
+    pub fn configure_price_limits(
+        ctx: Context<ConfigurePriceLimits>,
+        _organisation_id: String,
+        max_price_age: i64,
+        max_price_confidence_bps: u16,
+    ) -> Result<()> {
+        print!("Configuring price limits");
+        instructions::organisation::configure_price_limits(ctx, _organisation_id, max_price_age, max_price_confidence_bps)
+    }
+
+    pub fn add_admin(
+        ctx: Context<AddAdmin>,
+        _organisation_id: String,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        print!("Adding organisation admin");
+        instructions::organisation::add_admin(ctx, _organisation_id, new_admin)
+    }
+
+    pub fn remove_admin(
+        ctx: Context<RemoveAdmin>,
+        _organisation_id: String,
+        removed_admin: Pubkey,
+    ) -> Result<()> {
+        print!("Removing organisation admin");
+        instructions::organisation::remove_admin(ctx, _organisation_id, removed_admin)
+    }
+
+    pub fn configure_early_unlock(
+        ctx: Context<ConfigureEarlyUnlock>,
+        _organisation_id: String,
+        early_unlock_allowed: bool,
+        early_unlock_penalty_bps: u16,
+    ) -> Result<()> {
+        print!("Configuring early unlock");
+        instructions::organisation::configure_early_unlock(ctx, _organisation_id, early_unlock_allowed, early_unlock_penalty_bps)
+    }
+
+    pub fn set_contract_currency(
+        ctx: Context<SetContractCurrency>,
+        _organisation_id: String,
+        _employee_id: String,
+        currency: Currency,
+        price_feed: Pubkey,
+    ) -> Result<()> {
+        print!("Setting contract currency");
+        instructions::organisation::set_contract_currency(ctx, _organisation_id, _employee_id, currency, price_feed)
+    }
+
+    pub fn set_rate_commitment(
+        ctx: Context<SetRateCommitment>,
+        _organisation_id: String,
+        _employee_id: String,
+        rate: u64,
+        rate_commitment: [u8; 32],
+    ) -> Result<()> {
+        print!("Setting rate commitment");
+        instructions::organisation::set_rate_commitment(ctx, _organisation_id, _employee_id, rate, rate_commitment)
+    }
+
+    pub fn terminate_contract<'info>(
+        ctx: Context<'_, '_, '_, 'info, TerminateContract<'info>>,
+        _organisation_id: String,
+        _employee_id: String,
+        reason: TerminationReason,
+        severance_periods: u16,
+        pay_out_leave: bool,
+        rate_opening: Option<RateOpening>,
+    ) -> Result<()> {
+        print!("Terminating contract");
+        instructions::organisation::terminate_contract(ctx, _organisation_id, _employee_id, reason, severance_periods, pay_out_leave, rate_opening)
+    }
+
+    pub fn issue_loan(
+        ctx: Context<IssueLoan>,
+        _organisation_id: String,
+        _employee_id: String,
+        principal: u64,
+        interest_rate_bps: u16,
+        term: u16,
+    ) -> Result<()> {
+        print!("Issuing loan");
+        instructions::loan::issue_loan(ctx, _organisation_id, _employee_id, principal, interest_rate_bps, term)
+    }
+
+    pub fn repay_loan(
+        ctx: Context<RepayLoan>,
+        _organisation_id: String,
+        _employee_id: String,
+        amount: u64,
+    ) -> Result<()> {
+        print!("Repaying loan");
+        instructions::loan::repay_loan(ctx, _organisation_id, _employee_id, amount)
+    }
+
+    pub fn default_loan(
+        ctx: Context<ManageLoan>,
+        _organisation_id: String,
+        _employee_id: String,
+    ) -> Result<()> {
+        print!("Defaulting loan");
+        instructions::loan::default_loan(ctx, _organisation_id, _employee_id)
+    }
+
+    pub fn close_loan(
+        ctx: Context<CloseLoan>,
+        _organisation_id: String,
+        _employee_id: String,
+    ) -> Result<()> {
+        print!("Closing loan");
+        instructions::loan::close_loan(ctx, _organisation_id, _employee_id)
+    }
+
+    pub fn set_deduction_rules(
+        ctx: Context<SetDeductionRules>,
+        _organisation_id: String,
+        _employee_id: String,
+        rules: Vec<DeductionRule>,
+    ) -> Result<()> {
+        print!("Setting deduction rules");
+        instructions::deduction::set_deduction_rules(ctx, _organisation_id, _employee_id, rules)
+    }
+
+    pub fn set_contribution_rules(
+        ctx: Context<SetContributionRules>,
+        _organisation_id: String,
+        _employee_id: String,
+        rules: Vec<ContributionRule>,
+    ) -> Result<()> {
+        print!("Setting contribution rules");
+        instructions::contribution::set_contribution_rules(ctx, _organisation_id, _employee_id, rules)
+    }
+
+    pub fn get_funding_runway(
+        ctx: Context<GetFundingRunway>,
+        _organisation_id: String,
+        employee_ids: Vec<String>,
+        rate_openings: Vec<Option<RateOpening>>,
+    ) -> Result<FundingRunway> {
+        print!("Getting funding runway");
+        instructions::stream::get_funding_runway(ctx, _organisation_id, employee_ids, rate_openings)
+    }
+
+    pub fn submit_expense_claim(
+        ctx: Context<SubmitExpenseClaim>,
+        _organisation_id: String,
+        _employee_id: String,
+        receipt_hash: [u8; 32],
+        amount: u64,
+        category: ExpenseCategory,
+    ) -> Result<()> {
+        print!("Submitting expense claim");
+        instructions::expense::submit_expense_claim(ctx, _organisation_id, _employee_id, receipt_hash, amount, category)
+    }
+
+    pub fn approve_expense_claim(
+        ctx: Context<ResolveExpenseClaim>,
+        _organisation_id: String,
+        _employee_id: String,
+        _receipt_hash: [u8; 32],
+    ) -> Result<()> {
+        print!("Approving expense claim");
+        instructions::expense::approve_expense_claim(ctx, _organisation_id, _employee_id, _receipt_hash)
+    }
+
+    pub fn pay_expense_claim(
+        ctx: Context<PayExpenseClaim>,
+        _organisation_id: String,
+        _employee_id: String,
+        _receipt_hash: [u8; 32],
+    ) -> Result<()> {
+        print!("Paying expense claim");
+        instructions::expense::pay_expense_claim(ctx, _organisation_id, _employee_id, _receipt_hash)
+    }
+
+    pub fn reject_expense_claim(
+        ctx: Context<ResolveExpenseClaim>,
+        _organisation_id: String,
+        _employee_id: String,
+        _receipt_hash: [u8; 32],
+    ) -> Result<()> {
+        print!("Rejecting expense claim");
+        instructions::expense::reject_expense_claim(ctx, _organisation_id, _employee_id, _receipt_hash)
+    }
+
+    pub fn setup_milestone_contract(
+        ctx: Context<SetupMilestoneContract>,
+        _organisation_id: String,
+        _contract_id: String,
+        milestones: Vec<MilestoneArgs>,
+        dispute_window: i64,
+    ) -> Result<()> {
+        print!("Setting up milestone contract");
+        instructions::milestone::setup_milestone_contract(ctx, _organisation_id, _contract_id, milestones, dispute_window)
+    }
+
+    pub fn submit_milestone(
+        ctx: Context<SubmitMilestone>,
+        _organisation_id: String,
+        _contract_id: String,
+        index: u8,
+    ) -> Result<()> {
+        print!("Submitting milestone");
+        instructions::milestone::submit_milestone(ctx, _organisation_id, _contract_id, index)
+    }
+
+    pub fn dispute_milestone(
+        ctx: Context<ManageMilestone>,
+        _organisation_id: String,
+        _contract_id: String,
+        index: u8,
+    ) -> Result<()> {
+        print!("Disputing milestone");
+        instructions::milestone::dispute_milestone(ctx, _organisation_id, _contract_id, index)
+    }
+
+    pub fn approve_milestone(
+        ctx: Context<ReleaseMilestone>,
+        _organisation_id: String,
+        _contract_id: String,
+        index: u8,
+    ) -> Result<()> {
+        print!("Approving milestone");
+        instructions::milestone::approve_milestone(ctx, _organisation_id, _contract_id, index)
+    }
+
+    pub fn auto_release_milestone(
+        ctx: Context<ReleaseMilestone>,
+        _organisation_id: String,
+        _contract_id: String,
+        index: u8,
+    ) -> Result<()> {
+        print!("Auto releasing milestone");
+        instructions::milestone::auto_release_milestone(ctx, _organisation_id, _contract_id, index)
+    }
+
+    pub fn cancel_milestone_contract(
+        ctx: Context<CancelMilestoneContract>,
+        _organisation_id: String,
+        _contract_id: String,
+    ) -> Result<()> {
+        print!("Cancelling milestone contract");
+        instructions::milestone::cancel_milestone_contract(ctx, _organisation_id, _contract_id)
+    }
+
+    pub fn configure_leave(
+        ctx: Context<ConfigureLeave>,
+        _organisation_id: String,
+        _employee_id: String,
+        leave_accrual_rate: u64,
+    ) -> Result<()> {
+        print!("Configuring leave");
+        instructions::leave::configure_leave(ctx, _organisation_id, _employee_id, leave_accrual_rate)
+    }
+
+    pub fn request_leave(
+        ctx: Context<RequestLeave>,
+        _organisation_id: String,
+        _employee_id: String,
+        start_date: i64,
+        days: u16,
+    ) -> Result<()> {
+        print!("Requesting leave");
+        instructions::leave::request_leave(ctx, _organisation_id, _employee_id, start_date, days)
+    }
+
+    pub fn approve_leave(
+        ctx: Context<ApproveLeave>,
+        _organisation_id: String,
+        _employee_id: String,
+        _start_date: i64,
+    ) -> Result<()> {
+        print!("Approving leave");
+        instructions::leave::approve_leave(ctx, _organisation_id, _employee_id, _start_date)
+    }
+
+    pub fn reject_leave(
+        ctx: Context<RejectLeave>,
+        _organisation_id: String,
+        _employee_id: String,
+        _start_date: i64,
+    ) -> Result<()> {
+        print!("Rejecting leave");
+        instructions::leave::reject_leave(ctx, _organisation_id, _employee_id, _start_date)
     }
 
-    pub fn pay_contract(
-        ctx: Context<PayContract>,
+    pub fn submit_timesheet(
+        ctx: Context<SubmitTimesheet>,
         _organisation_id: String,
         _employee_id: String,
+        period_start: i64,
+        period_end: i64,
+        hours: u32,
+    ) -> Result<()> {
+        print!("Submitting timesheet");
+        instructions::timesheet::submit_timesheet(ctx, _organisation_id, _employee_id, period_start, period_end, hours)
+    }
+
+    pub fn approve_timesheet(
+        ctx: Context<ApproveTimesheet>,
+        _organisation_id: String,
+        _employee_id: String,
+        _period_start: i64,
+        rate_opening: Option<RateOpening>,
+    ) -> Result<()> {
+        print!("Approving timesheet");
+        instructions::timesheet::approve_timesheet(ctx, _organisation_id, _employee_id, _period_start, rate_opening)
+    }
+
+    pub fn reject_timesheet(
+        ctx: Context<RejectTimesheet>,
+        _organisation_id: String,
+        _employee_id: String,
+        _period_start: i64,
+    ) -> Result<()> {
+        print!("Rejecting timesheet");
+        instructions::timesheet::reject_timesheet(ctx, _organisation_id, _employee_id, _period_start)
+    }
+
+    pub fn pay_timesheet<'info>(
+        ctx: Context<'_, '_, '_, 'info, PayTimesheet<'info>>,
+        _organisation_id: String,
+        _employee_id: String,
+        _period_start: i64,
+        rate_opening: Option<RateOpening>,
+    ) -> Result<()> {
+        print!("Paying timesheet");
+        instructions::timesheet::pay_timesheet(ctx, _organisation_id, _employee_id, _period_start, rate_opening)
+    }
+
+    pub fn configure_dispute_policy(
+        ctx: Context<ConfigureDisputePolicy>,
+        _organisation_id: String,
+        dispute_window: i64,
+        arbiter: Pubkey,
+        dispute_approvals: u8,
+    ) -> Result<()> {
+        print!("Configuring dispute policy");
+        instructions::dispute::configure_dispute_policy(ctx, _organisation_id, dispute_window, arbiter, dispute_approvals)
+    }
+
+    pub fn open_dispute(
+        ctx: Context<OpenDispute>,
+        _organisation_id: String,
+        _employee_id: String,
+        _payment_index: u64,
         amount: u64,
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        print!("Opening dispute");
+        instructions::dispute::open_dispute(ctx, _organisation_id, _employee_id, _payment_index, amount, reason_hash)
+    }
+
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        _organisation_id: String,
+        _employee_id: String,
+        _payment_index: u64,
+        uphold: bool,
+        amount: u64,
+    ) -> Result<()> {
+        print!("Resolving dispute");
+        instructions::dispute::resolve_dispute(ctx, _organisation_id, _employee_id, _payment_index, uphold, amount)
+    }
+
+    pub fn execute_clawback(
+        ctx: Context<ExecuteClawback>,
+        _organisation_id: String,
+        _employee_id: String,
+        _payment_index: u64,
+    ) -> Result<()> {
+        print!("Executing clawback");
+        instructions::dispute::execute_clawback(ctx, _organisation_id, _employee_id, _payment_index)
+    }
+
+    pub fn pay_contract<'info>(
+        ctx: Context<'_, '_, '_, 'info, PayContract<'info>>,
+        _organisation_id: String,
+        _employee_id: String,
+        amount: u64,
+        payment_kind: PaymentKind,
+        rate_opening: Option<RateOpening>,
     ) -> Result<()> {
         print!("Paying contract");
-        instructions::stream::pay_contract(ctx, _organisation_id, _employee_id, amount)
+        instructions::stream::pay_contract(ctx, _organisation_id, _employee_id, amount, payment_kind, rate_opening)
+    }
+
+    pub fn setup_protocol_config(
+        ctx: Context<SetupProtocolConfig>,
+        fee_recipient: Pubkey,
+        performance_fee_bps: u16,
+    ) -> Result<()> {
+        print!("Setting up protocol config");
+        instructions::protocol::setup_protocol_config(ctx, fee_recipient, performance_fee_bps)
+    }
+
+    pub fn update_protocol_config(
+        ctx: Context<UpdateProtocolConfig>,
+        fee_recipient: Pubkey,
+        performance_fee_bps: u16,
+    ) -> Result<()> {
+        print!("Updating protocol config");
+        instructions::protocol::update_protocol_config(ctx, fee_recipient, performance_fee_bps)
+    }
+
+    pub fn configure_rebalancing(
+        ctx: Context<UpdateProtocolConfig>,
+        rebalance_drift_bps: u16,
+        rebalance_bounty_bps: u16,
+    ) -> Result<()> {
+        print!("Configuring rebalancing");
+        instructions::protocol::configure_rebalancing(ctx, rebalance_drift_bps, rebalance_bounty_bps)
     }
 
     pub fn withdraw_from_stream_wallet(
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+use crate::state::{Currency, EmployeeContract, Organisation};
+use crate::utils::BPS_DENOMINATOR;
+
+pub const DEFAULT_MAX_PRICE_AGE: i64 = 60;
+pub const DEFAULT_MAX_PRICE_CONFIDENCE_BPS: u16 = 100;
+
+// Layout of a Pyth v2 price account, only the fields read here
+const PRICE_ACCOUNT_MAGIC: u32 = 0xa1b2c3d4;
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGGREGATE_PRICE_OFFSET: usize = 208;
+const AGGREGATE_CONF_OFFSET: usize = 216;
+const AGGREGATE_STATUS_OFFSET: usize = 224;
+const PRICE_ACCOUNT_MIN_LEN: usize = 240;
+const STATUS_TRADING: u32 = 1;
+
+// Aggregate price of one unit of a fiat currency in the payout mint, `price * 10^expo`
+pub struct PriceQuote {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub trading: bool,
+}
+
+impl PriceQuote {
+    pub fn load(price_feed: &AccountInfo) -> Result<Self> {
+        let data = price_feed.try_borrow_data()?;
+        if data.len() < PRICE_ACCOUNT_MIN_LEN || read_u32(&data, 0) != PRICE_ACCOUNT_MAGIC {
+            return err!(NovaError::InvalidPriceFeed)
+        }
+
+        Ok(PriceQuote {
+            price: read_u64(&data, AGGREGATE_PRICE_OFFSET) as i64,
+            conf: read_u64(&data, AGGREGATE_CONF_OFFSET),
+            expo: read_u32(&data, EXPO_OFFSET) as i32,
+            publish_time: read_u64(&data, TIMESTAMP_OFFSET) as i64,
+            trading: read_u32(&data, AGGREGATE_STATUS_OFFSET) == STATUS_TRADING,
+        })
+    }
+
+    pub fn validate(&self, now: i64, max_age: i64, max_confidence_bps: u16) -> Result<()> {
+        if !self.trading || self.price <= 0 {
+            return err!(NovaError::InvalidPriceFeed)
+        }
+        if now.saturating_sub(self.publish_time) > max_age {
+            return err!(NovaError::StalePrice)
+        }
+        if self.conf as u128 * BPS_DENOMINATOR as u128 > self.price as u128 * max_confidence_bps as u128 {
+            return err!(NovaError::PriceConfidenceTooWide)
+        }
+        Ok(())
+    }
+
+    pub fn convert(&self, amount: u64) -> Result<u64> {
+        let scale = 10u128
+            .checked_pow(self.expo.unsigned_abs())
+            .ok_or(NovaError::MathOverflow)?;
+        let value = amount as u128 * self.price as u128;
+        let converted = if self.expo < 0 {
+            value / scale
+        } else {
+            value.checked_mul(scale).ok_or(NovaError::MathOverflow)?
+        };
+        u64::try_from(converted).map_err(|_| error!(NovaError::MathOverflow))
+    }
+}
+
+// Takes the contract's price feed off the remaining accounts and validates its quote against the
+// organisation's limits. Contracts denominated in USD are paid one to one and take no feed.
+pub fn load_contract_quote(
+    remaining_accounts: &mut std::slice::Iter<AccountInfo>,
+    employee_contract: &EmployeeContract,
+    organisation: &Organisation,
+    now: i64,
+) -> Result<Option<PriceQuote>> {
+    if employee_contract.currency == Currency::USD {
+        return Ok(None)
+    }
+
+    let price_feed = remaining_accounts.next().ok_or(NovaError::MissingPriceFeed)?;
+    if price_feed.key() != employee_contract.price_feed {
+        return err!(NovaError::InvalidPriceFeed)
+    }
+    let quote = PriceQuote::load(price_feed)?;
+    quote.validate(now, organisation.max_price_age, organisation.max_price_confidence_bps)?;
+    Ok(Some(quote))
+}
+
+// Converts an amount in the contract's currency to the payout mint
+pub fn to_payout_mint(quote: Option<&PriceQuote>, amount: u64) -> Result<u64> {
+    quote.map_or(Ok(amount), |quote| quote.convert(amount))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
\ No newline at end of file
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::utils::bps_of;
+
+pub const MAX_CONTRIBUTION_RULES: usize = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ContributionCategory {
+    Pension,
+    SocialSecurity,
+    Benefits,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ContributionRule {
+    pub category: ContributionCategory,
+    pub bps: u16,
+    pub destination: Pubkey,
+    pub paid_to_date: u64,
+}
+
+impl ContributionRule {
+    // Calculation of size: 1 + 2 + 32 + 8 = 43
+    pub const SIZE: usize = 43;
+
+    pub fn amount(&self, gross_amount: u64) -> u64 {
+        bps_of(gross_amount, self.bps)
+    }
+}
+
+#[account]
+pub struct ContributionSchedule {
+    pub employee_contract: Pubkey,
+    pub bump: u8,
+    pub rules: Vec<ContributionRule>,
+}
+
+impl ContributionSchedule {
+    // Calculation of size: 32 + 1 + 4 + 43 * 8 = 381
+    pub const SIZE: usize = 32 + 1 + 4 + ContributionRule::SIZE * MAX_CONTRIBUTION_RULES;
+
+    pub fn total_bps(&self) -> u64 {
+        self.rules.iter().map(|rule| rule.bps as u64).sum()
+    }
+}
\ No newline at end of file
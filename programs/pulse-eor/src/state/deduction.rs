@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::oracle::{to_payout_mint, PriceQuote};
+use crate::utils::{bps_of, BPS_DENOMINATOR};
+
+pub const MAX_DEDUCTION_RULES: usize = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DeductionCategory {
+    Tax,
+    Pension,
+    Benefits,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DeductionBasis {
+    Fixed,
+    Bps,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct DeductionRule {
+    pub category: DeductionCategory,
+    pub basis: DeductionBasis,
+    // Amount in the contract's currency for fixed rules, basis points of gross for bps rules
+    pub value: u64,
+    pub destination: Pubkey,
+}
+
+impl DeductionRule {
+    // Calculation of size: 1 + 1 + 8 + 32 = 42
+    pub const SIZE: usize = 42;
+
+    // Deduction in the payout mint, fixed amounts are converted at `quote`
+    pub fn amount(&self, gross_amount: u64, quote: Option<&PriceQuote>) -> Result<u64> {
+        match self.basis {
+            DeductionBasis::Fixed => to_payout_mint(quote, self.value),
+            DeductionBasis::Bps => Ok(bps_of(gross_amount, self.value.min(BPS_DENOMINATOR) as u16)),
+        }
+    }
+}
+
+#[account]
+pub struct DeductionSchedule {
+    pub employee_contract: Pubkey,
+    pub bump: u8,
+    pub rules: Vec<DeductionRule>,
+}
+
+impl DeductionSchedule {
+    // Calculation of size: 32 + 1 + 4 + 42 * 8 = 373
+    pub const SIZE: usize = 32 + 1 + 4 + DeductionRule::SIZE * MAX_DEDUCTION_RULES;
+}
\ No newline at end of file
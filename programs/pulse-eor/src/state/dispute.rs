@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_DISPUTE_ENTRIES: usize = 6;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeStatus {
+    Open,
+    Upheld,
+    Dismissed,
+    ClawedBack,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct DisputeEntry {
+    pub status: DisputeStatus,
+    pub actor: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+impl DisputeEntry {
+    // Calculation of size: 1 + 32 + 8 + 8 = 49
+    pub const SIZE: usize = 49;
+}
+
+#[account]
+pub struct Dispute {
+    pub receipt: Pubkey,
+    pub employee_contract: Pubkey,
+    pub reason_hash: [u8; 32],
+    pub status: DisputeStatus,
+    // Amount claimed when opened, then the amount approved for clawback on resolution
+    pub amount: u64,
+    pub clawed_back_amount: u64,
+    pub bump: u8,
+    // Audit trail of every transition with its actor
+    pub history: Vec<DisputeEntry>,
+}
+
+impl Dispute {
+    // Calculation of size: 32 + 32 + 32 + 1 + 8 + 8 + 1 + 4 + 49 * 6 = 412
+    pub const SIZE: usize = 32 + 32 + 32 + 1 + 8 + 8 + 1 + 4 + DisputeEntry::SIZE * MAX_DISPUTE_ENTRIES;
+
+    pub fn record(&mut self, status: DisputeStatus, actor: Pubkey, amount: u64, timestamp: i64) {
+        self.status = status;
+        self.history.push(DisputeEntry {
+            status,
+            actor,
+            amount,
+            timestamp,
+        });
+    }
+}
\ No newline at end of file
@@ -1,13 +1,223 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::error::NovaError;
+
+use crate::state::LEAVE_UNITS_PER_DAY;
+use crate::utils::bps_of;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ContractStatus {
+    Active,
+    Terminated,
+}
+
+// Fiat currency `rate` is denominated in, payments in anything but USD are converted into the
+// payout mint through the contract's price feed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Currency {
+    USD,
+    EUR,
+    GBP,
+    SGD,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TerminationReason {
+    None,
+    Resignation,
+    Dismissal,
+    Redundancy,
+    EndOfTerm,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PayMode {
+    Salaried,
+    // `rate` is paid per hour through approved timesheets
+    Hourly,
+}
+
+// Opening of a rate commitment, supplied with each instruction that prices from the rate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RateOpening {
+    pub rate: u64,
+    pub salt: [u8; 32],
+}
+
+impl RateOpening {
+    pub fn commitment(&self) -> [u8; 32] {
+        hashv(&[&self.rate.to_le_bytes(), &self.salt]).to_bytes()
+    }
+}
 
 #[account]
 pub struct EmployeeContract {
     pub payee: Pubkey,
     pub bump: u8,
     pub rate: u64,
+    // Length of one pay period in seconds, `rate` is earned per period
+    pub pay_period: i64,
+    pub last_paid_at: i64,
+    pub outstanding_advance: u64,
+    pub status: ContractStatus,
+    // Active `LoanAccount` repaid out of each payment, default when there is none
+    pub loan: Pubkey,
+    // `DeductionSchedule` applied to each payment, default when there is none
+    pub deductions: Pubkey,
+    // `ContributionSchedule` paid by the employer on top of each payment, default when there is none
+    pub contributions: Pubkey,
+    pub employer_contribution_bps: u16,
+    pub total_employer_contributions: u64,
+    // Number of payments made, seeds the next `PaymentReceipt`
+    pub payment_count: u64,
+    pub pay_mode: PayMode,
+    pub termination_reason: TerminationReason,
+    pub terminated_at: i64,
+    // Leave earned per pay period, in hundredths of a day
+    pub leave_accrual_rate: u64,
+    // Unused leave, in hundredths of a day
+    pub leave_balance: u64,
+    pub leave_accrued_at: i64,
+    // Approved leave beyond the balance, deducted from the next salary payment
+    pub unpaid_leave: u64,
+    pub currency: Currency,
+    // Price feed of `currency` in the payout mint, default for USD contracts
+    pub price_feed: Pubkey,
+    // Salted hash of the rate, `rate` is left at zero while set. Zero when the rate is public.
+    pub rate_commitment: [u8; 32],
+    // End of the latest submitted timesheet, timesheets can't start before it
+    pub last_timesheet_end: i64,
+    // Bump of the payee's holding wallet for the contract's organisation, which payments can also
+    // go to
+    pub holding_wallet_bump: u8,
+    // End of the latest requested leave, leave can't be requested before it
+    pub last_leave_end: i64,
+    // Layout version, fields added later are carved out of `reserved` and backfilled by migration
+    pub version: u8,
+    pub reserved: [u8; 64],
 }
 
 impl EmployeeContract {
-    // Calculation of size: 32 + 1 + 8 = 41
-    pub const SIZE: usize = 73;
+    // Calculation of size: 32 + 1 + 8 + 8 + 8 + 8 + 1 + 32 + 32 + 32 + 2 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 32 + 8 + 1 + 8 + 1 + 64 = 369
+    pub const SIZE: usize = 369;
+    pub const VERSION: u8 = 1;
+
+    // Backfills the fields added since the account's version with their defaults. Contracts
+    // written before the pay period was stored take `pay_period` and accrue from `now`.
+    pub fn migrate(&mut self, organisation_id: &str, pay_period: i64, now: i64) {
+        if self.pay_period <= 0 {
+            self.pay_period = pay_period;
+        }
+        if self.last_paid_at == 0 {
+            self.last_paid_at = now;
+        }
+        if self.version < 1 && self.leave_accrued_at == 0 {
+            self.leave_accrued_at = self.last_paid_at;
+        }
+        if self.version < 1 {
+            self.holding_wallet_bump = Self::find_holding_wallet_bump(&self.payee, organisation_id);
+        }
+        self.version = Self::VERSION;
+    }
+
+    pub fn find_holding_wallet_bump(payee: &Pubkey, organisation_id: &str) -> u8 {
+        Pubkey::find_program_address(&[b"holding-wallet", payee.as_ref(), organisation_id.as_bytes()], &crate::ID).1
+    }
+
+    pub fn holding_wallet(&self, organisation_id: &str) -> Pubkey {
+        Pubkey::create_program_address(
+            &[b"holding-wallet", self.payee.as_ref(), organisation_id.as_bytes(), &[self.holding_wallet_bump]],
+            &crate::ID,
+        ).unwrap_or_default()
+    }
+
+    // Payments can go to any token account of the payee or to their holding wallet for the
+    // contract's organisation
+    pub fn is_payee_token_account_owner(&self, owner: &Pubkey, organisation_id: &str) -> bool {
+        *owner == self.payee || *owner == self.holding_wallet(organisation_id)
+    }
+
+    pub fn has_rate_commitment(&self) -> bool {
+        self.rate_commitment != [0; 32]
+    }
+
+    // Rate of the contract, committed rates must come with their opening. Contracts without a
+    // pay period can't be priced until migration backfills it.
+    pub fn open_rate(&self, opening: Option<&RateOpening>) -> Result<u64> {
+        if self.pay_period <= 0 {
+            return err!(NovaError::InvalidPayPeriod)
+        }
+        if !self.has_rate_commitment() {
+            return Ok(self.rate)
+        }
+        match opening {
+            Some(opening) if opening.commitment() == self.rate_commitment => Ok(opening.rate),
+            _ => err!(NovaError::InvalidRateOpening),
+        }
+    }
+
+    pub fn has_loan(&self) -> bool {
+        self.loan != Pubkey::default()
+    }
+
+    pub fn has_deductions(&self) -> bool {
+        self.deductions != Pubkey::default()
+    }
+
+    pub fn has_contributions(&self) -> bool {
+        self.contributions != Pubkey::default()
+    }
+
+    // Employer cost of one pay period, salary plus employer contributions
+    pub fn cost_per_period(&self, rate: u64) -> u64 {
+        rate.saturating_add(bps_of(rate, self.employer_contribution_bps))
+    }
+
+    pub fn accrued_since_last_payment(&self, rate: u64, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.last_paid_at).max(0) as u128;
+        (rate as u128 * elapsed / self.pay_period.max(1) as u128) as u64
+    }
+
+    // Pay for `units` hundredths of a day of leave at the contract's daily rate
+    pub fn leave_payout(&self, rate: u64, units: u64) -> u64 {
+        (rate as u128 * units as u128 * 86_400
+            / (self.pay_period.max(1) as u128 * LEAVE_UNITS_PER_DAY as u128)) as u64
+    }
+
+    // Credits the leave earned over the pay periods completed since the last accrual
+    pub fn accrue_leave(&mut self, now: i64) {
+        let periods = now.saturating_sub(self.leave_accrued_at).max(0) / self.pay_period.max(1);
+        if periods > 0 {
+            self.leave_balance = self.leave_balance
+                .saturating_add(self.leave_accrual_rate.saturating_mul(periods as u64));
+            self.leave_accrued_at += periods * self.pay_period;
+        }
+    }
+
+    // Nets the value of outstanding unpaid leave out of a gross salary payment and returns the
+    // amount withheld, leave worth more than the payment carries over to the next one
+    pub fn withhold_unpaid_leave(&mut self, rate: u64, gross_amount: u64) -> u64 {
+        let value = self.leave_payout(rate, self.unpaid_leave);
+        if value <= gross_amount {
+            self.unpaid_leave = 0;
+            return value
+        }
+        self.unpaid_leave = (self.unpaid_leave as u128 * (value - gross_amount) as u128 / value as u128) as u64;
+        gross_amount
+    }
+
+    // Advance limit in the contract's currency, outstanding advances are held in the payout mint
+    // and netted off once the limit is converted
+    pub fn advance_limit(&self, rate: u64, now: i64, advance_limit_bps: u16) -> u64 {
+        bps_of(self.accrued_since_last_payment(rate, now), advance_limit_bps)
+    }
+
+    // Nets any outstanding advance out of a gross payment and returns the amount recovered
+    pub fn recover_advance(&mut self, gross_amount: u64) -> u64 {
+        let recovered = self.outstanding_advance.min(gross_amount);
+        self.outstanding_advance -= recovered;
+        recovered
+    }
 }
\ No newline at end of file
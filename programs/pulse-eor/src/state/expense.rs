@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ExpenseCategory {
+    Travel,
+    Equipment,
+    Meals,
+    Software,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ExpenseClaimStatus {
+    Submitted,
+    Approved,
+    Paid,
+    Rejected,
+}
+
+#[account]
+pub struct ExpenseClaim {
+    pub employee_contract: Pubkey,
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub category: ExpenseCategory,
+    pub receipt_hash: [u8; 32],
+    pub status: ExpenseClaimStatus,
+    pub submitted_at: i64,
+    pub resolved_at: i64,
+    pub resolver: Pubkey,
+    pub bump: u8,
+}
+
+impl ExpenseClaim {
+    // Calculation of size: 32 + 32 + 8 + 1 + 32 + 1 + 8 + 8 + 32 + 1 = 155
+    pub const SIZE: usize = 155;
+}
\ No newline at end of file
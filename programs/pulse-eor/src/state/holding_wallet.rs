@@ -1,15 +1,131 @@
 use anchor_lang::prelude::*;
 
+use crate::error::NovaError;
+
+pub const MAX_LOCK_BUCKETS: usize = 5;
+pub const MAX_LOCK_NAME_LEN: usize = 32;
+pub const MAX_GUARDIANS: usize = 5;
+// Recovery needs more than one guardian so no single guardian can take over the wallet
+pub const MIN_GUARDIAN_THRESHOLD: u8 = 2;
+// Seconds the owner has to cancel a recovery before it can complete
+pub const RECOVERY_DELAY: i64 = 3 * 86_400;
+
+// Part of the holding wallet balance that can't be withdrawn before `unlock_at`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LockBucket {
+    pub name: String,
+    pub amount: u64,
+    pub unlock_at: i64,
+}
+
+impl LockBucket {
+    // Calculation of size: 4 + 32 + 8 + 8 = 52
+    pub const SIZE: usize = 4 + MAX_LOCK_NAME_LEN + 8 + 8;
+}
+
 #[account]
 pub struct HoldingWalletState {
+    // Seeds the holding wallet with the organisation id, authority over it is held by `owner`
     pub payee: Pubkey,
     pub meteora_allocation: u8,
     pub holding_allocation: u8,
     pub bump: u8,
     pub wallet_bump: u8,
+    // Cost basis, in the underlying token, of the LP the holding wallet currently holds in the
+    // Meteora vault. The wallet is the organisation's alone, so this covers all of its LP.
+    pub principal: u64,
+    pub locks: Vec<LockBucket>,
+    // Key allowed to manage and withdraw from the holding wallet, starts as the payee and moves
+    // through guardian recovery
+    pub owner: Pubkey,
+    // Withdraws up to `delegate_limit` per `delegate_period` seconds, default when there is none
+    pub delegate: Pubkey,
+    pub delegate_limit: u64,
+    pub delegate_period: i64,
+    pub delegate_period_start: i64,
+    pub delegate_spent: u64,
+    // `guardian_threshold` of the guardians can move `owner` to a new key
+    pub guardians: Vec<Pubkey>,
+    pub guardian_threshold: u8,
+    // Owner the guardians are recovering the wallet to, default when no recovery is pending
+    pub pending_owner: Pubkey,
+    pub recovery_available_at: i64,
+    // Layout version, fields added later are carved out of `reserved` and backfilled by migration
+    pub version: u8,
+    pub reserved: [u8; 64],
 }
 
 impl HoldingWalletState {
-    // Calculation of size: 32 + 1 + 1 + 1 + 1 = 36
-    pub const SIZE: usize = 36;
-}
\ No newline at end of file
+    // Calculation of size: 32 + 1 + 1 + 1 + 1 + 8 + 4 + 52 * 5 + 32 + 32 + 8 + 8 + 8 + 8 + 4 + 32 * 5 + 1 + 32 + 8 + 1 + 64 = 674
+    pub const SIZE: usize = 44 + 4 + LockBucket::SIZE * MAX_LOCK_BUCKETS + 32 + 32 + 8 + 8 + 8 + 8 + 4 + 32 * MAX_GUARDIANS + 1 + 32 + 8 + 1 + 64;
+    pub const VERSION: u8 = 1;
+
+    // Backfills the fields added since the account's version with their defaults. States written
+    // before versioning used the holding wallet shared by all of the payee's organisations and move
+    // to the organisation's own wallet with bump `wallet_bump`.
+    pub fn migrate(&mut self, wallet_bump: u8) {
+        if self.version < 1 {
+            if self.owner == Pubkey::default() {
+                self.owner = self.payee;
+            }
+            self.wallet_bump = wallet_bump;
+        }
+        self.version = Self::VERSION;
+    }
+
+    pub fn has_pending_recovery(&self) -> bool {
+        self.pending_owner != Pubkey::default()
+    }
+
+    pub fn has_delegate(&self) -> bool {
+        self.delegate != Pubkey::default()
+    }
+
+    pub fn can_withdraw(&self, key: &Pubkey) -> bool {
+        *key == self.owner || (self.has_delegate() && *key == self.delegate)
+    }
+
+    // Counts `amount` against the delegate's limit for the current period, starting a new period
+    // once the previous one has elapsed
+    pub fn spend_delegate_allowance(&mut self, amount: u64, now: i64) -> Result<()> {
+        if now >= self.delegate_period_start.saturating_add(self.delegate_period) {
+            let elapsed_periods = (now - self.delegate_period_start) / self.delegate_period.max(1);
+            self.delegate_period_start += elapsed_periods * self.delegate_period.max(1);
+            self.delegate_spent = 0;
+        }
+        let spent = self.delegate_spent.checked_add(amount).ok_or(NovaError::MathOverflow)?;
+        if spent > self.delegate_limit {
+            return err!(NovaError::DelegateLimitExceeded)
+        }
+        self.delegate_spent = spent;
+        Ok(())
+    }
+
+    // Balance held in buckets that are still locked at `now`
+    pub fn locked_amount(&self, now: i64) -> u64 {
+        self.locks
+            .iter()
+            .filter(|bucket| bucket.unlock_at > now)
+            .fold(0u64, |total, bucket| total.saturating_add(bucket.amount))
+    }
+
+    // Fails when taking `amount` out of a total holding balance of `balance` would dip into locked funds
+    pub fn ensure_unlocked(&self, balance: u64, amount: u64, now: i64) -> Result<()> {
+        if amount > balance.saturating_sub(self.locked_amount(now)) {
+            return err!(NovaError::FundsLocked)
+        }
+        Ok(())
+    }
+
+    // Releases the average cost basis of `lp_amount` out of `lp_balance`, the holding wallet's LP
+    // balance, and returns (cost_basis, realised_yield) for an exit worth `underlying_amount`.
+    pub fn realise(&mut self, lp_amount: u64, lp_balance: u64, underlying_amount: u64) -> (u64, u64) {
+        let cost_basis = if lp_balance == 0 || lp_amount >= lp_balance {
+            self.principal
+        } else {
+            (self.principal as u128 * lp_amount as u128 / lp_balance as u128) as u64
+        };
+        self.principal = self.principal.saturating_sub(cost_basis);
+        (cost_basis, underlying_amount.saturating_sub(cost_basis))
+    }
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+// Leave balances and accrual rates are kept in hundredths of a day
+pub const LEAVE_UNITS_PER_DAY: u64 = 100;
+pub const SECONDS_PER_LEAVE_DAY: i64 = 86_400;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LeaveRequestStatus {
+    Requested,
+    Approved,
+}
+
+#[account]
+pub struct LeaveRequest {
+    pub employee_contract: Pubkey,
+    pub start_date: i64,
+    pub days: u16,
+    pub status: LeaveRequestStatus,
+    // Part of the request covered by the leave balance, in hundredths of a day
+    pub paid_units: u64,
+    // Part of the request beyond the leave balance, deducted from the next salary of salaried contracts
+    pub unpaid_units: u64,
+    pub approver: Pubkey,
+    pub requested_at: i64,
+    pub bump: u8,
+}
+
+impl LeaveRequest {
+    // Calculation of size: 32 + 8 + 2 + 1 + 8 + 8 + 32 + 8 + 1 = 100
+    pub const SIZE: usize = 100;
+
+    pub fn end_date(&self) -> i64 {
+        leave_end(self.start_date, self.days)
+    }
+}
+
+pub fn leave_end(start_date: i64, days: u16) -> i64 {
+    start_date.saturating_add(days as i64 * SECONDS_PER_LEAVE_DAY)
+}
\ No newline at end of file
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LoanStatus {
+    Active,
+    Repaid,
+    Defaulted,
+}
+
+// Loans are disbursed and repaid in the payout mint, so their amounts are token amounts whatever
+// the contract's currency
+#[account]
+pub struct LoanAccount {
+    pub employee_contract: Pubkey,
+    pub lender_token_account: Pubkey,
+    pub principal: u64,
+    pub interest_rate_bps: u16,
+    // Number of scheduled installments
+    pub term: u16,
+    pub installment_amount: u64,
+    pub remaining_balance: u64,
+    pub installments_paid: u16,
+    pub status: LoanStatus,
+    pub issued_at: i64,
+    pub bump: u8,
+}
+
+impl LoanAccount {
+    // Calculation of size: 32 + 32 + 8 + 2 + 2 + 8 + 8 + 2 + 1 + 8 + 1 = 104
+    pub const SIZE: usize = 104;
+
+    // Applies a repayment and returns the amount actually taken against the balance
+    pub fn apply_repayment(&mut self, amount: u64) -> u64 {
+        let repaid = amount.min(self.remaining_balance);
+        self.remaining_balance -= repaid;
+        if self.remaining_balance == 0 {
+            self.status = LoanStatus::Repaid;
+        }
+        repaid
+    }
+}
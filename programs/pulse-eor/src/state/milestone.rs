@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_MILESTONES: usize = 10;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MilestoneStatus {
+    Pending,
+    Submitted,
+    Disputed,
+    Released,
+    Cancelled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MilestoneContractStatus {
+    Active,
+    Completed,
+    Cancelled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Milestone {
+    pub amount: u64,
+    // Earliest time the milestone can be released without an admin
+    pub due_date: i64,
+    pub status: MilestoneStatus,
+    pub submitted_at: i64,
+}
+
+impl Milestone {
+    // Calculation of size: 8 + 8 + 1 + 8 = 25
+    pub const SIZE: usize = 25;
+}
+
+#[account]
+pub struct MilestoneContract {
+    pub contractor: Pubkey,
+    pub mint: Pubkey,
+    // Seconds after submission before a milestone can be released without admin approval
+    pub dispute_window: i64,
+    pub total_amount: u64,
+    pub released_amount: u64,
+    pub status: MilestoneContractStatus,
+    pub bump: u8,
+    pub escrow_bump: u8,
+    pub milestones: Vec<Milestone>,
+}
+
+impl MilestoneContract {
+    // Calculation of size: 32 + 32 + 8 + 8 + 8 + 1 + 1 + 1 + 4 + 25 * 10 = 345
+    pub const SIZE: usize = 32 + 32 + 8 + 8 + 8 + 1 + 1 + 1 + 4 + Milestone::SIZE * MAX_MILESTONES;
+
+    pub fn unreleased_amount(&self) -> u64 {
+        self.total_amount - self.released_amount
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.milestones
+            .iter()
+            .all(|milestone| matches!(milestone.status, MilestoneStatus::Released | MilestoneStatus::Cancelled))
+    }
+}
\ No newline at end of file
@@ -1,7 +1,31 @@
 pub use holding_wallet::*;
 pub use organisation::*;
 pub use employee::*;
+pub use protocol::*;
+pub use loan::*;
+pub use deduction::*;
+pub use contribution::*;
+pub use receipt::*;
+pub use expense::*;
+pub use milestone::*;
+pub use timesheet::*;
+pub use dispute::*;
+pub use leave::*;
+pub use payout::*;
+pub use standing_order::*;
 
 pub mod holding_wallet;
 pub mod organisation;
-pub mod employee;
\ No newline at end of file
+pub mod employee;
+pub mod protocol;
+pub mod loan;
+pub mod deduction;
+pub mod contribution;
+pub mod receipt;
+pub mod expense;
+pub mod milestone;
+pub mod timesheet;
+pub mod dispute;
+pub mod leave;
+pub mod payout;
+pub mod standing_order;
\ No newline at end of file
@@ -1,14 +1,72 @@
 use anchor_lang::prelude::*;
 
+use crate::oracle::{DEFAULT_MAX_PRICE_AGE, DEFAULT_MAX_PRICE_CONFIDENCE_BPS};
+use crate::state::{PaymentKind, PaymentPolicy, PAYMENT_KIND_COUNT};
+
+pub const MAX_ADMINS: usize = 64;
+
 #[account]
 pub struct Organisation {
+    // Grows and shrinks with the account, see `space`
     pub admins: Vec<Pubkey>,
     pub bump: u8,
     pub stream_wallet_bump: u8,
     pub treasury_delegate_bump: u8,
     pub stream_authority: Pubkey,
+    pub advance_limit_bps: u16,
+    pub advance_fee: u64,
+    // Approval requirements indexed by `PaymentKind`
+    pub payment_policies: [PaymentPolicy; PAYMENT_KIND_COUNT],
+    // Seconds after a payment during which it can be disputed
+    pub dispute_window: i64,
+    // Resolves disputes alone, otherwise `dispute_approvals` admins must sign
+    pub arbiter: Pubkey,
+    pub dispute_approvals: u8,
+    // Limits on the price feeds used to convert non-USD contracts
+    pub max_price_age: i64,
+    pub max_price_confidence_bps: u16,
+    // Whether holding wallet lock buckets can be released before they expire, at a penalty paid
+    // to the streaming wallet
+    pub early_unlock_allowed: bool,
+    pub early_unlock_penalty_bps: u16,
+    // Layout version, fields added later are carved out of `reserved` and backfilled by migration
+    pub version: u8,
+    pub reserved: [u8; 64],
 }
 
 impl Organisation {
-    pub const SIZE: usize = 1 + 1 + 1 + 32 * 10 + 32;
+    // Size with an empty admin list
+    pub const SIZE: usize = 4 + 1 + 1 + 1 + 32 + 2 + 8 + PaymentPolicy::SIZE * PAYMENT_KIND_COUNT + 8 + 32 + 1 + 8 + 2 + 1 + 2 + 1 + 64;
+    pub const VERSION: u8 = 1;
+
+    // Backfills the fields added since the account's version with their defaults
+    pub fn migrate(&mut self) {
+        if self.version < 1 && self.max_price_age == 0 {
+            self.max_price_age = DEFAULT_MAX_PRICE_AGE;
+            self.max_price_confidence_bps = DEFAULT_MAX_PRICE_CONFIDENCE_BPS;
+        }
+        self.version = Self::VERSION;
+    }
+
+    // Account space for `admin_count` admins, the account is reallocated as admins come and go
+    pub fn space(admin_count: usize) -> usize {
+        8 + Self::SIZE + 32 * admin_count
+    }
+
+    // Admins needed to meet every approval requirement, and at least one to manage the organisation
+    pub fn min_admins(&self) -> usize {
+        self.payment_policies
+            .iter()
+            .map(|policy| policy.required_approvals)
+            .fold(self.dispute_approvals, u8::max)
+            .max(1) as usize
+    }
+
+    pub fn is_admin(&self, key: &Pubkey) -> bool {
+        self.admins.contains(key)
+    }
+
+    pub fn payment_policy(&self, kind: PaymentKind) -> &PaymentPolicy {
+        &self.payment_policies[kind as usize]
+    }
 }
\ No newline at end of file
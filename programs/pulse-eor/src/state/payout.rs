@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_PAYOUT_DESTINATIONS: usize = 5;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PayoutDestination {
+    pub destination: Pubkey,
+    pub bps: u16,
+}
+
+impl PayoutDestination {
+    // Calculation of size: 32 + 2 = 34
+    pub const SIZE: usize = 34;
+}
+
+// Split of everything paid out to `owner`, empty to pay a single token account
+#[account]
+pub struct PayoutInstructions {
+    pub owner: Pubkey,
+    pub bump: u8,
+    pub destinations: Vec<PayoutDestination>,
+}
+
+impl PayoutInstructions {
+    // Calculation of size: 32 + 1 + 4 + 34 * 5 = 207
+    pub const SIZE: usize = 32 + 1 + 4 + PayoutDestination::SIZE * MAX_PAYOUT_DESTINATIONS;
+
+    pub fn address(owner: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"payout-instructions", owner.as_ref()], &crate::ID).0
+    }
+}
\ No newline at end of file
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::utils::bps_of;
+
+pub const DEFAULT_REBALANCE_DRIFT_BPS: u16 = 500;
+
+#[account]
+pub struct ProtocolConfig {
+    pub authority: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub performance_fee_bps: u16,
+    pub bump: u8,
+    // Drift of a holding wallet's vault share from its allocation beyond which keepers can rebalance it
+    pub rebalance_drift_bps: u16,
+    // Share of the yield realised by a rebalance paid to the keeper
+    pub rebalance_bounty_bps: u16,
+    // Layout version, fields added later are carved out of `reserved` and backfilled by migration
+    pub version: u8,
+    pub reserved: [u8; 64],
+}
+
+impl ProtocolConfig {
+    // Calculation of size: 32 + 32 + 2 + 1 + 2 + 2 + 1 + 64 = 136
+    pub const SIZE: usize = 136;
+    pub const VERSION: u8 = 1;
+
+    // Backfills the fields added since the account's version with their defaults
+    pub fn migrate(&mut self) {
+        if self.version < 1 && self.rebalance_drift_bps == 0 {
+            self.rebalance_drift_bps = DEFAULT_REBALANCE_DRIFT_BPS;
+        }
+        self.version = Self::VERSION;
+    }
+
+    pub fn performance_fee(&self, realised_yield: u64) -> u64 {
+        bps_of(realised_yield, self.performance_fee_bps)
+    }
+
+    pub fn rebalance_bounty(&self, realised_yield: u64) -> u64 {
+        bps_of(realised_yield, self.rebalance_bounty_bps)
+    }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Currency;
+
+pub const PAYMENT_KIND_COUNT: usize = 5;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentKind {
+    Salary,
+    Bonus,
+    Commission,
+    Reimbursement,
+    Severance,
+}
+
+impl PaymentKind {
+    // Only salary settles time worked, other kinds leave the accrual clock untouched
+    pub fn follows_rate_schedule(&self) -> bool {
+        *self == PaymentKind::Salary
+    }
+
+    // Severance is the last pay of a contract, so it settles any outstanding loan in full
+    pub fn is_final_pay(&self) -> bool {
+        *self == PaymentKind::Severance
+    }
+
+    // Reimbursements are paid gross, without deductions, contributions or recoveries
+    pub fn is_taxable(&self) -> bool {
+        *self != PaymentKind::Reimbursement
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct PaymentPolicy {
+    // Payments above this amount need `required_approvals` distinct admin signatures, 0 disables
+    pub approval_threshold: u64,
+    pub required_approvals: u8,
+}
+
+impl PaymentPolicy {
+    // Calculation of size: 8 + 1 = 9
+    pub const SIZE: usize = 9;
+
+    pub fn approvals_for(&self, amount: u64) -> u8 {
+        if self.approval_threshold > 0 && amount > self.approval_threshold {
+            self.required_approvals
+        } else {
+            0
+        }
+    }
+}
+
+#[account]
+pub struct PaymentReceipt {
+    pub employee_contract: Pubkey,
+    pub payment_index: u64,
+    pub kind: PaymentKind,
+    // Earned amount in the payout mint, net of any unpaid leave withheld and before deductions
+    pub gross_amount: u64,
+    pub total_deductions: u64,
+    pub employer_contributions: u64,
+    pub net_amount: u64,
+    pub payer: Pubkey,
+    pub paid_at: i64,
+    pub bump: u8,
+    // Amount requested in the contract's currency and the price it was converted at
+    pub currency: Currency,
+    pub contract_amount: u64,
+    pub fx_price: i64,
+    pub fx_expo: i32,
+    // Withheld by the mint's transfer fee extension, `net_amount` is what the payee was credited
+    pub transfer_fee: u64,
+    // Holding wallet token account credited by the payment and the amount it received, the most
+    // a dispute on this receipt can claw back
+    pub holding_wallet_token_account: Pubkey,
+    pub holding_wallet_amount: u64,
+}
+
+impl PaymentReceipt {
+    // Calculation of size: 32 + 8 + 1 + 8 + 8 + 8 + 8 + 32 + 8 + 1 + 1 + 8 + 8 + 4 + 8 + 32 + 8 = 183
+    pub const SIZE: usize = 183;
+}
\ No newline at end of file
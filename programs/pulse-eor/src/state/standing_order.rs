@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::error::NovaError;
+
+// Recurring transfer of `amount` from the payee's holding wallet to `destination`, executed by
+// any keeper once `next_execution_at` has passed. The interval is a fixed number of seconds, so
+// calendar schedules such as the 1st of each month can't be expressed and drift over time.
+#[account]
+pub struct StandingOrder {
+    pub payee: Pubkey,
+    pub holding_wallet_state: Pubkey,
+    pub destination: Pubkey,
+    // Holding wallet owner that created the order, it lapses once ownership moves to another key
+    pub owner: Pubkey,
+    pub amount: u64,
+    // Seconds between executions
+    pub interval: i64,
+    pub next_execution_at: i64,
+    pub execution_count: u64,
+    pub bump: u8,
+}
+
+impl StandingOrder {
+    // Calculation of size: 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1 = 161
+    pub const SIZE: usize = 161;
+
+    pub fn is_due(&self, now: i64) -> bool {
+        now >= self.next_execution_at
+    }
+
+    // Moves the schedule to the first execution after `now`, executions missed while the order
+    // wasn't executed are skipped rather than caught up
+    pub fn schedule_next(&mut self, now: i64) -> Result<()> {
+        let missed = now.saturating_sub(self.next_execution_at).max(0) / self.interval + 1;
+        self.next_execution_at = missed
+            .checked_mul(self.interval)
+            .and_then(|offset| self.next_execution_at.checked_add(offset))
+            .ok_or(NovaError::MathOverflow)?;
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TimesheetStatus {
+    Submitted,
+    Approved,
+    Paid,
+}
+
+#[account]
+pub struct Timesheet {
+    pub employee_contract: Pubkey,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub hours: u32,
+    pub status: TimesheetStatus,
+    pub payout: u64,
+    pub approver: Pubkey,
+    pub submitted_at: i64,
+    pub payment_index: u64,
+    pub bump: u8,
+}
+
+impl Timesheet {
+    // Calculation of size: 32 + 8 + 8 + 4 + 1 + 8 + 32 + 8 + 8 + 1 = 110
+    pub const SIZE: usize = 110;
+}
\ No newline at end of file
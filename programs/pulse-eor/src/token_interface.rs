@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{get_return_data, invoke, invoke_signed};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::system_instruction;
+use anchor_spl::token::spl_token::{self, instruction::TokenInstruction};
+
+use crate::error::NovaError;
+use crate::utils::BPS_DENOMINATOR;
+
+// Payroll flows accept accounts of either token program. Both share the SPL Token base layout,
+// Token-2022 appends an account type byte and its extensions after it. Holding wallet flows stay
+// on SPL Token since the yield vault only holds SPL Token mints, so payouts refuse to send
+// Token-2022 mints to a holding wallet.
+// TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    6, 221, 246, 225, 238, 117, 143, 222, 24, 66, 93, 188, 228, 108, 205, 218, 182, 26, 252, 77, 131, 185, 13, 39,
+    254, 189, 249, 40, 216, 161, 139, 252,
+]);
+
+const ACCOUNT_TYPE_OFFSET: usize = spl_token::state::Account::LEN;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+// Offsets into the transfer fee config: two authorities and the withheld amount, then the older
+// and newer fees as (epoch, maximum fee, basis points)
+const OLDER_TRANSFER_FEE_OFFSET: usize = 72;
+const NEWER_TRANSFER_FEE_OFFSET: usize = 90;
+const TRANSFER_FEE_CONFIG_LEN: usize = 108;
+
+pub fn is_token_program(key: &Pubkey) -> bool {
+    *key == spl_token::ID || *key == TOKEN_2022_PROGRAM_ID
+}
+
+pub fn token_account(info: &AccountInfo) -> Result<spl_token::state::Account> {
+    let data = load_base(info, spl_token::state::Account::LEN, ACCOUNT_TYPE_ACCOUNT)?;
+    spl_token::state::Account::unpack(&data).map_err(|_| error!(NovaError::InvalidTokenAccount))
+}
+
+pub fn mint(info: &AccountInfo) -> Result<spl_token::state::Mint> {
+    let data = load_base(info, spl_token::state::Mint::LEN, ACCOUNT_TYPE_MINT)?;
+    spl_token::state::Mint::unpack(&data).map_err(|_| error!(NovaError::InvalidTokenAccount))
+}
+
+fn load_base(info: &AccountInfo, len: usize, account_type: u8) -> Result<Vec<u8>> {
+    if !is_token_program(info.owner) {
+        return err!(NovaError::InvalidTokenAccount)
+    }
+    let data = info.try_borrow_data()?;
+    let extended = data.len() > ACCOUNT_TYPE_OFFSET && data[ACCOUNT_TYPE_OFFSET] == account_type;
+    if data.len() != len && !extended {
+        return err!(NovaError::InvalidTokenAccount)
+    }
+    Ok(data[..len].to_vec())
+}
+
+// Fee the mint's transfer fee extension withholds from `amount` at the destination, zero for
+// SPL Token mints and Token-2022 mints without the extension
+pub fn transfer_fee(mint_info: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint_info.owner != TOKEN_2022_PROGRAM_ID || amount == 0 {
+        return Ok(0)
+    }
+    let data = mint_info.try_borrow_data()?;
+    if data.len() <= ACCOUNT_TYPE_OFFSET {
+        return Ok(0)
+    }
+
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value = offset + 4;
+        if extension_type == 0 || value + length > data.len() {
+            break
+        }
+        if extension_type == EXTENSION_TRANSFER_FEE_CONFIG && length >= TRANSFER_FEE_CONFIG_LEN {
+            let epoch = Clock::get()?.epoch;
+            let newer_epoch = read_u64(&data, value + NEWER_TRANSFER_FEE_OFFSET);
+            let fee_offset = if epoch >= newer_epoch {
+                value + NEWER_TRANSFER_FEE_OFFSET
+            } else {
+                value + OLDER_TRANSFER_FEE_OFFSET
+            };
+            let maximum_fee = read_u64(&data, fee_offset + 8);
+            let bps = u16::from_le_bytes([data[fee_offset + 16], data[fee_offset + 17]]) as u128;
+            let scaled = amount as u128 * bps;
+            let fee = scaled / BPS_DENOMINATOR as u128 + u128::from(scaled % BPS_DENOMINATOR as u128 != 0);
+            return Ok((fee as u64).min(maximum_fee))
+        }
+        offset = value + length;
+    }
+
+    Ok(0)
+}
+
+// Amount to send so that `amount` arrives after the mint's transfer fee
+pub fn gross_up_transfer_fee(mint_info: &AccountInfo, amount: u64) -> Result<u64> {
+    let mut gross = amount;
+    loop {
+        let next = amount
+            .checked_add(transfer_fee(mint_info, gross)?)
+            .ok_or(NovaError::MathOverflow)?;
+        if next <= gross {
+            return Ok(gross)
+        }
+        gross = next;
+    }
+}
+
+pub fn transfer_checked<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint_info: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    if !is_token_program(token_program.key) {
+        return err!(NovaError::InvalidTokenProgram)
+    }
+    let decimals = mint(&mint_info)?.decimals;
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: vec![
+            AccountMeta::new(from.key(), false),
+            AccountMeta::new_readonly(mint_info.key(), false),
+            AccountMeta::new(to.key(), false),
+            AccountMeta::new_readonly(authority.key(), true),
+        ],
+        data: TokenInstruction::TransferChecked { amount, decimals }.pack(),
+    };
+    invoke_signed(&instruction, &[from, mint_info, to, authority, token_program], signer)?;
+    Ok(())
+}
+
+// Creates `account` as a token account of `mint` owned by `owner`, sized by the token program
+// for any extensions the mint requires. `signer` signs for the new account's address. An address
+// that was sent lamports ahead of time is topped up, allocated and assigned instead, as `create_account`
+// would fail on it.
+pub fn create_token_account<'info>(
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    mint_info: AccountInfo<'info>,
+    owner: &Pubkey,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    if !is_token_program(token_program.key) || mint_info.owner != token_program.key {
+        return err!(NovaError::InvalidTokenProgram)
+    }
+
+    let size_instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: vec![AccountMeta::new_readonly(mint_info.key(), false)],
+        data: TokenInstruction::GetAccountDataSize.pack(),
+    };
+    invoke(&size_instruction, &[mint_info.clone(), token_program.clone()])?;
+    let space = match get_return_data() {
+        Some((program_id, data)) if program_id == token_program.key() && data.len() == 8 => {
+            u64::from_le_bytes(data[..8].try_into().unwrap())
+        }
+        _ => return err!(NovaError::InvalidTokenProgram),
+    };
+
+    let lamports = Rent::get()?.minimum_balance(space as usize);
+    let current_lamports = account.lamports();
+    if current_lamports == 0 {
+        invoke_signed(
+            &system_instruction::create_account(payer.key, account.key, lamports, space, token_program.key),
+            &[payer, account.clone(), system_program],
+            signer,
+        )?;
+    } else {
+        if current_lamports < lamports {
+            invoke(
+                &system_instruction::transfer(payer.key, account.key, lamports - current_lamports),
+                &[payer, account.clone(), system_program.clone()],
+            )?;
+        }
+        invoke_signed(
+            &system_instruction::allocate(account.key, space),
+            &[account.clone(), system_program.clone()],
+            signer,
+        )?;
+        invoke_signed(
+            &system_instruction::assign(account.key, token_program.key),
+            &[account.clone(), system_program],
+            signer,
+        )?;
+    }
+
+    let initialize_instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: vec![
+            AccountMeta::new(account.key(), false),
+            AccountMeta::new_readonly(mint_info.key(), false),
+        ],
+        data: TokenInstruction::InitializeAccount3 { owner: *owner }.pack(),
+    };
+    invoke(&initialize_instruction, &[account, mint_info, token_program])?;
+    Ok(())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
\ No newline at end of file
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use mercurial_vault::state::Vault;
+
+use crate::error::NovaError;
+use crate::token_interface::transfer_checked;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+pub fn bps_of(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+// Mirrors the vault's own share pricing so LP balances can be valued in the underlying token.
+pub fn lp_to_underlying(vault: &Vault, lp_amount: u64, lp_supply: u64) -> Result<u64> {
+    if lp_amount == 0 || lp_supply == 0 {
+        return Ok(0);
+    }
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    vault
+        .get_amount_by_share(current_time, lp_amount, lp_supply)
+        .ok_or_else(|| error!(NovaError::MathOverflow))
+}
+
+// The vault pays withdrawals out of its token reserve and refuses them while disabled
+pub fn vault_can_pay_out(vault: &Vault, token_vault_balance: u64, amount: u64) -> bool {
+    vault.enabled == 1 && token_vault_balance >= amount
+}
+
+// LP to burn for at least `amount` of the underlying token, rounded up against the vault's pricing.
+pub fn underlying_to_lp(vault: &Vault, amount: u64, lp_supply: u64) -> Result<u64> {
+    if amount == 0 {
+        return Ok(0);
+    }
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    vault
+        .get_unmint_amount(current_time, amount, lp_supply)
+        .and_then(|lp_amount| lp_amount.checked_add(1))
+        .ok_or_else(|| error!(NovaError::MathOverflow))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_from_streaming_wallet<'info>(
+    token_program: AccountInfo<'info>,
+    streaming_wallet: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    organisation_id: &str,
+    stream_wallet_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds = &[
+        b"streaming-wallet",
+        organisation_id.as_bytes(),
+        &[stream_wallet_bump],
+    ];
+
+    transfer_checked(token_program, from, mint, to, streaming_wallet, &[&signer_seeds[..]], amount)
+}
\ No newline at end of file
@@ -8,7 +8,8 @@ import {
     Commitment,
     TransactionMessage,
     VersionedTransaction,
-    Keypair
+    Keypair,
+    Transaction,
 } from "@solana/web3.js";
 
 import {
@@ -23,6 +24,7 @@ import {
     getAssociatedTokenAddress,
 } from "@solana/spl-token";
 import { BN } from "bn.js";
+import { expect } from "chai";
 
 describe("pulse-eor", () => {
     // Configure the client to use the local cluster.
@@ -52,6 +54,7 @@ describe("pulse-eor", () => {
     const streamingWalletSeed = "streaming-wallet";
     const employeeContractSeed = "employee-contract";
     const employeeRate = 1
+    const payPeriod = 60 * 60 * 24 * 30
 
     const organisationWallet = Keypair.generate();
     const employeeWallet = Keypair.generate();
@@ -137,6 +140,9 @@ describe("pulse-eor", () => {
                 organisationId,
                 employeeId,
                 new BN(employeeRate * 10 ** 10),
+                new BN(payPeriod),
+                { salaried: {} },
+                Array(32).fill(0),
             ).accounts({
                 employeeContract: employeeContractAccount[0],
                 organisation: organisationAccount[0],
@@ -239,19 +245,35 @@ describe("pulse-eor", () => {
 
         console.log("Signature created employee token account ", signature1);
 
+        const receiptAccount = PublicKey.findProgramAddressSync(
+            [Buffer.from("payment-receipt"), employeeContractAccount[0].toBuffer(), new BN(0).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const payoutInstructionsAccount = PublicKey.findProgramAddressSync(
+            [Buffer.from("payout-instructions"), employeeWallet.publicKey.toBuffer()],
+            program.programId
+        );
+
         const instruction = await program.methods.payContract(
             organisationId,
             employeeId,
             new BN(employeeRate * 10 ** 10),
+            { salary: {} },
+            null,
         ).accounts({
             employeeContract: employeeContractAccount[0],
             organisation: organisationAccount[0],
             streamingWallet: streamingWalletAccount[0],
             streamingWalletTokenAccount: streamingWalletTokenAccount,
             employeeTokenAccount: employeeWalletTokenAccount,
+            receipt: receiptAccount[0],
+            mint: token,
             payer: streamAuthority.publicKey,
             tokenProgram: TOKEN_PROGRAM_ID,
-        }).instruction()
+            systemProgram: SystemProgram.programId,
+        }).remainingAccounts([
+            { pubkey: payoutInstructionsAccount[0], isSigner: false, isWritable: false },
+        ]).instruction()
 
         const blockhash = await connection.getLatestBlockhash();
         const transactionMessage = new TransactionMessage({
@@ -315,6 +337,7 @@ describe("pulse-eor", () => {
             streamingWalletTokenAccount: streamingWalletTokenAccount,
             payer: adminWallet.publicKey,
             withdraweeTokenAccount: adminTokenAccount,
+            mint: token,
             tokenProgram: TOKEN_PROGRAM_ID,
         }).instruction()
 
@@ -348,4 +371,469 @@ describe("pulse-eor", () => {
         
     })
 
+    describe("rules", () => {
+        const rulesOrganisationId = "rules";
+        const rulesEmployeeId = "rules-1";
+        const bpfLoaderUpgradeable = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+        const provider = program.provider as anchor.AnchorProvider;
+
+        const payee = Keypair.generate();
+        const treasury = Keypair.generate();
+        const guardians = [Keypair.generate(), Keypair.generate()];
+        const newOwner = Keypair.generate();
+
+        const rulesOrganisation = PublicKey.findProgramAddressSync(
+            [Buffer.from("organisation"), Buffer.from(rulesOrganisationId)],
+            program.programId
+        )[0];
+        const rulesStreamingWallet = PublicKey.findProgramAddressSync(
+            [Buffer.from("streaming-wallet"), Buffer.from(rulesOrganisationId)],
+            program.programId
+        )[0];
+        const treasuryDelegate = PublicKey.findProgramAddressSync(
+            [Buffer.from("treasury-delegate"), Buffer.from(rulesOrganisationId), treasury.publicKey.toBuffer()],
+            program.programId
+        )[0];
+        const rulesContract = PublicKey.findProgramAddressSync(
+            [Buffer.from("employee-contract"), Buffer.from(rulesOrganisationId), Buffer.from(rulesEmployeeId)],
+            program.programId
+        )[0];
+        const holdingWallet = PublicKey.findProgramAddressSync(
+            [Buffer.from("holding-wallet"), payee.publicKey.toBuffer(), Buffer.from(rulesOrganisationId)],
+            program.programId
+        )[0];
+        const holdingWalletState = PublicKey.findProgramAddressSync(
+            [Buffer.from("holding-state"), payee.publicKey.toBuffer(), Buffer.from(rulesOrganisationId)],
+            program.programId
+        )[0];
+        const protocolConfig = PublicKey.findProgramAddressSync(
+            [Buffer.from("protocol-config")],
+            program.programId
+        )[0];
+        const programData = PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            bpfLoaderUpgradeable
+        )[0];
+
+        const expectError = async (promise: Promise<unknown>, code: string) => {
+            try {
+                await promise;
+            } catch (e) {
+                expect(e.error?.errorCode?.code, e.toString()).to.equal(code);
+                return;
+            }
+            expect.fail(`expected ${code}`);
+        }
+
+        const createTokenAccount = async (owner: PublicKey) => {
+            const address = getAssociatedTokenAddressSync(token, owner, true);
+            await provider.sendAndConfirm(new Transaction().add(
+                createAssociatedTokenAccountIdempotentInstruction(adminWallet.publicKey, address, owner, token)
+            ), [adminWallet]);
+            return address;
+        }
+
+        const airdrop = async (key: PublicKey) => {
+            const signature = await connection.requestAirdrop(key, 10000000000);
+            await confirmSignature(signature, commitment);
+        }
+
+        before(async () => {
+            await airdrop(payee.publicKey);
+
+            await program.methods.setupOrganisation(
+                rulesOrganisationId,
+            ).accounts({
+                organisation: rulesOrganisation,
+                streamingWallet: rulesStreamingWallet,
+                treasuryDelegate,
+                treasury: treasury.publicKey,
+                admin: adminWallet.publicKey,
+                streamAuthority: streamAuthority.publicKey,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                adminWallet,
+                streamAuthority,
+            ]).rpc();
+
+            await program.methods.setupEmployeeContract(
+                rulesOrganisationId,
+                rulesEmployeeId,
+                new BN(employeeRate * 10 ** 10),
+                new BN(payPeriod),
+                { salaried: {} },
+                Array(32).fill(0),
+            ).accounts({
+                employeeContract: rulesContract,
+                organisation: rulesOrganisation,
+                payee: payee.publicKey,
+                payer: adminWallet.publicKey,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                adminWallet,
+            ]).rpc();
+
+            await program.methods.setupHoldingWallet(
+                rulesOrganisationId,
+            ).accounts({
+                holdingWallet,
+                holdingWalletState,
+                organisation: rulesOrganisation,
+                employee: payee.publicKey,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                payee,
+            ]).rpc();
+        });
+
+        it("only sets up holding wallets for existing organisations", async () => {
+            const madeUpOrganisationId = "made-up";
+            await expectError(program.methods.setupHoldingWallet(
+                madeUpOrganisationId,
+            ).accounts({
+                holdingWallet: PublicKey.findProgramAddressSync(
+                    [Buffer.from("holding-wallet"), payee.publicKey.toBuffer(), Buffer.from(madeUpOrganisationId)],
+                    program.programId
+                )[0],
+                holdingWalletState: PublicKey.findProgramAddressSync(
+                    [Buffer.from("holding-state"), payee.publicKey.toBuffer(), Buffer.from(madeUpOrganisationId)],
+                    program.programId
+                )[0],
+                organisation: PublicKey.findProgramAddressSync(
+                    [Buffer.from("organisation"), Buffer.from(madeUpOrganisationId)],
+                    program.programId
+                )[0],
+                employee: payee.publicKey,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                payee,
+            ]).rpc(), "AccountNotInitialized");
+        });
+
+        it("rejects a committed rate set up alongside a plaintext rate", async () => {
+            const contract = PublicKey.findProgramAddressSync(
+                [Buffer.from("employee-contract"), Buffer.from(rulesOrganisationId), Buffer.from("rules-2")],
+                program.programId
+            )[0];
+            await expectError(program.methods.setupEmployeeContract(
+                rulesOrganisationId,
+                "rules-2",
+                new BN(employeeRate * 10 ** 10),
+                new BN(payPeriod),
+                { salaried: {} },
+                Array(32).fill(1),
+            ).accounts({
+                employeeContract: contract,
+                organisation: rulesOrganisation,
+                payee: payee.publicKey,
+                payer: adminWallet.publicKey,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                adminWallet,
+            ]).rpc(), "InvalidRateOpening");
+        });
+
+        it("only lets the upgrade authority set up the protocol fees within bounds", async () => {
+            await expectError(program.methods.setupProtocolConfig(
+                adminWallet.publicKey,
+                1000,
+            ).accounts({
+                protocolConfig,
+                authority: adminWallet.publicKey,
+                program: program.programId,
+                programData,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                adminWallet,
+            ]).rpc(), "NotUpgradeAuthority");
+
+            await expectError(program.methods.setupProtocolConfig(
+                adminWallet.publicKey,
+                10001,
+            ).accounts({
+                protocolConfig,
+                authority: provider.wallet.publicKey,
+                program: program.programId,
+                programData,
+                systemProgram: SystemProgram.programId,
+            }).rpc(), "InvalidFeeBps");
+
+            await program.methods.setupProtocolConfig(
+                adminWallet.publicKey,
+                1000,
+            ).accounts({
+                protocolConfig,
+                authority: provider.wallet.publicKey,
+                program: program.programId,
+                programData,
+                systemProgram: SystemProgram.programId,
+            }).rpc();
+            const config = await program.account.protocolConfig.fetch(protocolConfig);
+            expect(config.performanceFeeBps).to.equal(1000);
+
+            await expectError(program.methods.configureRebalancing(
+                500,
+                9001,
+            ).accounts({
+                protocolConfig,
+                authority: provider.wallet.publicKey,
+            }).rpc(), "InvalidFeeBps");
+        });
+
+        it("caps advances at the configured share of accrued pay", async () => {
+            await expectError(program.methods.configureAdvances(
+                rulesOrganisationId,
+                10001,
+                new BN(0),
+            ).accounts({
+                organisation: rulesOrganisation,
+                admin: adminWallet.publicKey,
+            }).signers([
+                adminWallet,
+            ]).rpc(), "InvalidFeeBps");
+
+            await program.methods.configureAdvances(
+                rulesOrganisationId,
+                5000,
+                new BN(0),
+            ).accounts({
+                organisation: rulesOrganisation,
+                admin: adminWallet.publicKey,
+            }).signers([
+                adminWallet,
+            ]).rpc();
+
+            const streamingWalletTokenAccount = await createTokenAccount(rulesStreamingWallet);
+            const payeeTokenAccount = await createTokenAccount(payee.publicKey);
+            await expectError(program.methods.requestAdvance(
+                rulesOrganisationId,
+                rulesEmployeeId,
+                new BN(employeeRate * 10 ** 10),
+                null,
+            ).accounts({
+                employeeContract: rulesContract,
+                organisation: rulesOrganisation,
+                streamingWallet: rulesStreamingWallet,
+                streamingWalletTokenAccount,
+                payeeTokenAccount,
+                mint: token,
+                payee: payee.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            }).signers([
+                payee,
+            ]).rpc(), "AdvanceLimitExceeded");
+        });
+
+        it("issues loans with rounded up installments and rejects empty terms", async () => {
+            const loan = PublicKey.findProgramAddressSync(
+                [Buffer.from("loan"), rulesContract.toBuffer()],
+                program.programId
+            )[0];
+            const adminTokenAccount = await createTokenAccount(adminWallet.publicKey);
+            const payeeTokenAccount = await createTokenAccount(payee.publicKey);
+            await confirmSignature(await mintTo(connection, adminWallet, token, adminTokenAccount, mint, 10 ** 10), commitment);
+
+            const issueLoan = (principal: number, term: number) => program.methods.issueLoan(
+                rulesOrganisationId,
+                rulesEmployeeId,
+                new BN(principal),
+                1000,
+                term,
+            ).accounts({
+                employeeContract: rulesContract,
+                organisation: rulesOrganisation,
+                loan,
+                admin: adminWallet.publicKey,
+                adminTokenAccount,
+                employeeTokenAccount: payeeTokenAccount,
+                lenderTokenAccount: adminTokenAccount,
+                mint: token,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                adminWallet,
+            ]).rpc();
+
+            await expectError(issueLoan(10 ** 10, 0), "InvalidLoanTerms");
+
+            await issueLoan(10 ** 10, 3);
+            const loanAccount = await program.account.loanAccount.fetch(loan);
+            expect(loanAccount.remainingBalance.toString()).to.equal("11000000000");
+            expect(loanAccount.installmentAmount.toString()).to.equal("3666666667");
+            expect(loanAccount.installmentsPaid).to.equal(0);
+            const contract = await program.account.employeeContract.fetch(rulesContract);
+            expect(contract.loan.toBase58()).to.equal(loan.toBase58());
+        });
+
+        it("refuses to release a lock bucket that doesn't exist", async () => {
+            const holdingWalletTokenAccount = await createTokenAccount(holdingWallet);
+            const streamingWalletTokenAccount = await createTokenAccount(rulesStreamingWallet);
+            await expectError(program.methods.releaseLock(
+                rulesOrganisationId,
+                "missing",
+            ).accounts({
+                holdingWalletState,
+                holdingWallet,
+                payee: payee.publicKey,
+                holdingWalletTokenAccount,
+                organisation: rulesOrganisation,
+                streamingWallet: rulesStreamingWallet,
+                streamingWalletTokenAccount,
+                employee: payee.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            }).signers([
+                payee,
+            ]).rpc(), "InvalidLockBucket");
+        });
+
+        it("requires a delegate period and only the owner can set a delegate", async () => {
+            await expectError(program.methods.setHoldingDelegate(
+                rulesOrganisationId,
+                newOwner.publicKey,
+                new BN(100),
+                new BN(0),
+            ).accounts({
+                holdingWalletState,
+                payee: payee.publicKey,
+                owner: payee.publicKey,
+            }).signers([
+                payee,
+            ]).rpc(), "InvalidDelegate");
+
+            await expectError(program.methods.setHoldingDelegate(
+                rulesOrganisationId,
+                newOwner.publicKey,
+                new BN(100),
+                new BN(60),
+            ).accounts({
+                holdingWalletState,
+                payee: payee.publicKey,
+                owner: newOwner.publicKey,
+            }).signers([
+                newOwner,
+            ]).rpc(), "NotHoldingWalletOwner");
+
+            await program.methods.setHoldingDelegate(
+                rulesOrganisationId,
+                newOwner.publicKey,
+                new BN(100),
+                new BN(60),
+            ).accounts({
+                holdingWalletState,
+                payee: payee.publicKey,
+                owner: payee.publicKey,
+            }).signers([
+                payee,
+            ]).rpc();
+            const state = await program.account.holdingWalletState.fetch(holdingWalletState);
+            expect(state.delegate.toBase58()).to.equal(newOwner.publicKey.toBase58());
+            expect(state.delegateLimit.toNumber()).to.equal(100);
+        });
+
+        it("delays guardian recovery until the owner can no longer cancel it", async () => {
+            const setGuardians = (keys: PublicKey[], threshold: number) => program.methods.setGuardians(
+                rulesOrganisationId,
+                keys,
+                threshold,
+            ).accounts({
+                holdingWalletState,
+                payee: payee.publicKey,
+                owner: payee.publicKey,
+            }).signers([
+                payee,
+            ]).rpc();
+            const recover = (signers: Keypair[]) => program.methods.recoverHoldingWallet(
+                rulesOrganisationId,
+                newOwner.publicKey,
+            ).accounts({
+                holdingWalletState,
+                payee: payee.publicKey,
+            }).remainingAccounts(
+                signers.map((signer) => ({ pubkey: signer.publicKey, isSigner: true, isWritable: false }))
+            ).signers(signers).rpc();
+            const complete = () => program.methods.completeHoldingWalletRecovery(
+                rulesOrganisationId,
+            ).accounts({
+                holdingWalletState,
+                payee: payee.publicKey,
+            }).rpc();
+            const cancel = () => program.methods.cancelHoldingWalletRecovery(
+                rulesOrganisationId,
+            ).accounts({
+                holdingWalletState,
+                payee: payee.publicKey,
+                owner: payee.publicKey,
+            }).signers([
+                payee,
+            ]).rpc();
+
+            await expectError(setGuardians([guardians[0].publicKey], 1), "InvalidGuardians");
+            await setGuardians(guardians.map((guardian) => guardian.publicKey), 2);
+
+            await expectError(recover([guardians[0]]), "MissingApprovals");
+            await recover(guardians);
+            let state = await program.account.holdingWalletState.fetch(holdingWalletState);
+            expect(state.owner.toBase58()).to.equal(payee.publicKey.toBase58());
+            expect(state.pendingOwner.toBase58()).to.equal(newOwner.publicKey.toBase58());
+
+            await expectError(recover(guardians), "RecoveryPending");
+            await expectError(complete(), "RecoveryNotReady");
+
+            await cancel();
+            state = await program.account.holdingWalletState.fetch(holdingWalletState);
+            expect(state.owner.toBase58()).to.equal(payee.publicKey.toBase58());
+            expect(state.pendingOwner.toBase58()).to.equal(PublicKey.default.toBase58());
+            await expectError(cancel(), "RecoveryNotReady");
+        });
+
+        it("migrates only outdated accounts of the expected type", async () => {
+            await expectError(program.methods.migrateEmployeeContract(
+                rulesOrganisationId,
+                rulesEmployeeId,
+                new BN(payPeriod),
+            ).accounts({
+                employeeContract: rulesContract,
+                organisation: rulesOrganisation,
+                admin: payee.publicKey,
+                payer: payee.publicKey,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                payee,
+            ]).rpc(), "PayerIsNotAdmin");
+
+            await expectError(program.methods.migrateEmployeeContract(
+                rulesOrganisationId,
+                rulesEmployeeId,
+                new BN(payPeriod),
+            ).accounts({
+                employeeContract: rulesContract,
+                organisation: rulesOrganisation,
+                admin: adminWallet.publicKey,
+                payer: adminWallet.publicKey,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                adminWallet,
+            ]).rpc(), "AlreadyMigrated");
+
+            await expectError(program.methods.migrateOrganisation().accounts({
+                account: rulesContract,
+                payer: adminWallet.publicKey,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                adminWallet,
+            ]).rpc(), "InvalidMigration");
+
+            await expectError(program.methods.migrateHoldingWalletState(
+                rulesOrganisationId,
+            ).accounts({
+                holdingWalletState,
+                holdingWallet,
+                payee: payee.publicKey,
+                payer: adminWallet.publicKey,
+                systemProgram: SystemProgram.programId,
+            }).signers([
+                adminWallet,
+            ]).rpc(), "AlreadyMigrated");
+        });
+    });
 });
\ No newline at end of file